@@ -0,0 +1,72 @@
+use bff::{Engine, Program, Storage};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use std::io;
+
+/// Puts and gets cells that lie 100 cells apart, diagonally, in a region of 2000x2000 cells.
+const FAR_APART: &str = "45*>:\"d\"*\"x\"\\:p:\"d\"*:g$1-:v\n   ^                      _@\n";
+/// Counts down from 10000, putting and getting a cell next to the code on the way.
+const TIGHT_LOOP: &str = "\"d\":*>:12p12g$1-:v\n     ^           _@\n";
+/// Wraps around the edges of a region 1000001 cells wide, twice per iteration, 20 times.
+const WRAP_AROUND: &str =
+    "\"v\"a::**:*1p\">\"a::**:*2p45*v\n   <                       <\n1-:|\n   @\n";
+/// Queries the least and the greatest point of a region of 1000x1000 cells with `y`, 100 times.
+const SYSINFO: &str = "\"x\"\"d\"a*1-:p\"d\">dy$ey$1-:v\n               ^         _@\n";
+/// Counts down from 10000, doing some arithmetic on the way, in a loop of simple instructions.
+const ARITHMETIC: &str = "aa*:*>:1+2*3%4+5\\-$1-:v\n     ^    #  <        _@\n";
+
+fn run(source: &str, storage: Storage) {
+    run_with(source, storage, Engine::Interpreter);
+}
+
+fn run_with(source: &str, storage: Storage, engine: Engine) {
+    Program::<i32>::builder(source)
+        .storage(storage)
+        .engine(engine)
+        .stdout(io::sink())
+        .build()
+        .run()
+        .unwrap();
+}
+
+fn funge_space(c: &mut Criterion) {
+    let mut group = c.benchmark_group("funge_space");
+    group.sample_size(20);
+    for (name, source) in [("far_apart", FAR_APART), ("tight_loop", TIGHT_LOOP)] {
+        for storage in [Storage::Dense, Storage::Sparse] {
+            let id = BenchmarkId::new(name, format!("{:?}", storage).to_lowercase());
+            group.bench_with_input(id, source, |b, source| b.iter(|| run(source, storage)));
+        }
+    }
+    group.finish();
+}
+
+fn wrap_around(c: &mut Criterion) {
+    let mut group = c.benchmark_group("wrap_around");
+    group.sample_size(10);
+    for storage in [Storage::Dense, Storage::Sparse] {
+        let id = format!("{:?}", storage).to_lowercase();
+        group.bench_function(id, |b| b.iter(|| run(WRAP_AROUND, storage)));
+    }
+    group.finish();
+}
+
+fn sysinfo(c: &mut Criterion) {
+    c.bench_function("sysinfo/bounds", |b| {
+        b.iter(|| run(SYSINFO, Storage::Dense))
+    });
+}
+
+fn engine(c: &mut Criterion) {
+    let mut group = c.benchmark_group("engine");
+    group.sample_size(20);
+    for engine in [Engine::Interpreter, Engine::Fast] {
+        let id = format!("{:?}", engine).to_lowercase();
+        group.bench_function(id, |b| {
+            b.iter(|| run_with(ARITHMETIC, Storage::Dense, engine))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, funge_space, wrap_around, sysinfo, engine);
+criterion_main!(benches);
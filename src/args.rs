@@ -1,13 +1,15 @@
-use clap::Parser;
+use clap::{ArgEnum, Parser, Subcommand};
 use std::path::PathBuf;
 
 #[derive(Parser, Debug)]
 #[clap(author, version, about, long_about = None)]
 pub struct Args {
+    #[clap(subcommand)]
+    pub command: Option<Command>,
     #[clap(
         name = "file",
         conflicts_with = "Unefunge program",
-        help = "The path to the Unefunge/Befunge 98 source file"
+        help = "The path to the Unefunge/Befunge/Trefunge 98 source file"
     )]
     pub file: Option<PathBuf>,
     #[clap(
@@ -17,4 +19,209 @@ pub struct Args {
         help = "Run the Unefunge 98 program provided in the command line"
     )]
     pub ucode: Option<String>,
+    #[clap(
+        long = "exec",
+        global = true,
+        arg_enum,
+        default_value = "disabled",
+        help = "The operating paradigm of the `=` (execute) instruction; beware, `=` can run arbitrary commands"
+    )]
+    pub paradigm: Paradigm,
+    #[clap(
+        long = "cell-size",
+        global = true,
+        arg_enum,
+        default_value = "i32",
+        help = "The type of the cells of Funge-Space and of the stacks; bignum cells never overflow"
+    )]
+    pub cell_size: CellSize,
+    #[clap(
+        long = "storage",
+        global = true,
+        arg_enum,
+        default_value = "auto",
+        help = "The way Funge-Space is stored; sparse storage suits cells that lie far apart"
+    )]
+    pub storage: Storage,
+    #[clap(
+        long = "engine",
+        global = true,
+        arg_enum,
+        default_value = "interpreter",
+        help = "The engine that executes the program; the fast one executes runs of simple instructions at once"
+    )]
+    pub engine: Engine,
+    #[clap(
+        long = "trefunge",
+        global = true,
+        help = "Run the source file as a Trefunge 98 (3D) program; implied for `.tf` files"
+    )]
+    pub trefunge: bool,
+    #[clap(
+        long = "std",
+        global = true,
+        arg_enum,
+        help = "The language standard of the source file; 93 is implied for `.bf` files and 98 for any other file"
+    )]
+    pub standard: Option<Standard>,
+    #[clap(
+        long = "trace",
+        help = "Trace the execution, as a JSON object per executed instruction (see the README), on stderr"
+    )]
+    pub trace: bool,
+    #[clap(
+        long = "trace-file",
+        requires = "trace",
+        help = "Write the trace to the given file instead of stderr"
+    )]
+    pub trace_file: Option<PathBuf>,
+    #[clap(
+        long = "trace-top",
+        requires = "trace",
+        value_name = "N",
+        help = "The number of values of the TOSS to trace [default: 5]"
+    )]
+    pub trace_top: Option<usize>,
+    #[clap(
+        long = "trace-region",
+        requires = "trace",
+        value_name = "FROM:TO",
+        allow_hyphen_values = true,
+        help = "Trace only the instructions from FROM to TO (both inclusive), like `0,0:79,24`"
+    )]
+    pub trace_region: Option<String>,
+    #[clap(
+        long = "trace-instructions",
+        requires = "trace",
+        value_name = "INSTRUCTIONS",
+        allow_hyphen_values = true,
+        help = "Trace only the given instructions, like `pg`"
+    )]
+    pub trace_instructions: Option<String>,
+    #[clap(
+        long = "profile",
+        help = "Profile the execution and write a summary and a heat map of the executed cells to stderr at exit"
+    )]
+    pub profile: bool,
+    #[clap(
+        long = "profile-csv",
+        requires = "profile",
+        value_name = "FILE",
+        help = "Also write the executions of every cell to the given file, as a CSV matrix"
+    )]
+    pub profile_csv: Option<PathBuf>,
+    #[clap(
+        long = "profile-json",
+        requires = "profile",
+        value_name = "FILE",
+        help = "Also write the executions of every cell to the given file, as a JSON matrix"
+    )]
+    pub profile_json: Option<PathBuf>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Run a source file under an interactive debugger
+    Debug {
+        #[clap(help = "The path to the Unefunge/Befunge/Trefunge 98 source file")]
+        file: PathBuf,
+    },
+    /// Run a source file in a full-screen visualiser, which shows the IPs moving through Funge-Space
+    #[clap(alias = "visualize")]
+    Visualise {
+        #[clap(help = "The path to the Unefunge/Befunge/Trefunge 98 source file")]
+        file: PathBuf,
+    },
+    /// Compile a Befunge-93/98 source file ahead of time, to a standalone Rust program
+    Compile {
+        #[clap(help = "The path to the Befunge-93/98 source file")]
+        file: PathBuf,
+        #[clap(
+            short = 'o',
+            long = "output",
+            help = "Write the Rust source code to the given file instead of stdout"
+        )]
+        output: Option<PathBuf>,
+    },
+}
+
+/// The language standard of a program.
+#[derive(ArgEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Standard {
+    /// Befunge-93: a fixed 80x25 Funge-Space and a much smaller instruction set
+    #[clap(name = "93")]
+    Befunge93,
+    /// Funge-98
+    #[clap(name = "98")]
+    Funge98,
+}
+
+/// The type of the cells of a program.
+#[derive(ArgEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CellSize {
+    I32,
+    I64,
+    I128,
+    /// Arbitrary precision integers
+    Bignum,
+}
+
+/// The operating paradigm of the `=` (execute) instruction; see `bff::Paradigm`.
+#[derive(ArgEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Paradigm {
+    /// `=` is unavailable and reflects
+    Disabled,
+    /// Commands are interpreted by the system shell, like the C `system()` call does
+    System,
+    /// Commands are split by whitespace and executed directly, without a shell
+    Exec,
+}
+
+impl From<Paradigm> for bff::Paradigm {
+    fn from(paradigm: Paradigm) -> Self {
+        match paradigm {
+            Paradigm::Disabled => bff::Paradigm::Disabled,
+            Paradigm::System => bff::Paradigm::System,
+            Paradigm::Exec => bff::Paradigm::Exec,
+        }
+    }
+}
+
+/// The way Funge-Space is stored; see `bff::Storage`.
+#[derive(ArgEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Storage {
+    /// Dense, until a cell is written so far away that the box of cells would get huge
+    Auto,
+    /// Always dense
+    Dense,
+    /// Always sparse
+    Sparse,
+}
+
+impl From<Storage> for bff::Storage {
+    fn from(storage: Storage) -> Self {
+        match storage {
+            Storage::Auto => bff::Storage::Auto,
+            Storage::Dense => bff::Storage::Dense,
+            Storage::Sparse => bff::Storage::Sparse,
+        }
+    }
+}
+
+/// The engine that executes a program; see `bff::Engine`.
+#[derive(ArgEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Engine {
+    /// Decode and execute every instruction as it is reached
+    Interpreter,
+    /// Cache and execute the runs of simple instructions at once
+    Fast,
+}
+
+impl From<Engine> for bff::Engine {
+    fn from(engine: Engine) -> Self {
+        match engine {
+            Engine::Interpreter => bff::Engine::Interpreter,
+            Engine::Fast => bff::Engine::Fast,
+        }
+    }
 }
@@ -0,0 +1,496 @@
+use anyhow::{anyhow, bail, Context, Result};
+use bff::{format_value, Exit, FungeInteger, Program};
+use std::{fmt, io, io::prelude::*};
+
+const PROMPT: &str = "(bff) ";
+
+const HELP: &str = "\
+step [N]                        execute the next N (default 1) instructions
+continue                        execute instructions until a breakpoint or the end of the program
+break cell X [Y [Z]]            stop before an IP executes the cell at (X, Y, Z)
+break instruction C             stop before an IP executes the instruction C
+break stack depth|top OP V      stop before an IP executes anything while the depth or the top
+                                of its TOSS compares to V as OP (one of == != < <= > >=) says
+watch X [Y [Z]]                 stop after the cell at (X, Y, Z) is written to
+breakpoints                     list the breakpoints and the watchpoints
+delete N                        delete the breakpoint or watchpoint N
+ip                              show the IPs, in the order they execute
+grid [R]                        show the cells up to R (default 5) cells around the IP
+stack                           show the TOSS and the SOSS of the IP
+stacks                          show the whole stack stack of the IP
+set cell X [Y [Z]] V            set the value of the cell at (X, Y, Z) to V
+set stack N V                   set the Nth value of the TOSS (0 is the top) to V
+push V                          push V onto the TOSS
+pop                             pop a value from the TOSS
+help                            show this message
+quit                            quit the debugger
+
+Values are either numbers or characters preceded by a single quote, like 'A.";
+
+/// A comparison between a property of the TOSS and a value.
+#[derive(Clone, Copy)]
+enum Comparison {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Comparison {
+    fn parse(s: &str) -> Result<Self> {
+        Ok(match s {
+            "==" => Comparison::Eq,
+            "!=" => Comparison::Ne,
+            "<" => Comparison::Lt,
+            "<=" => Comparison::Le,
+            ">" => Comparison::Gt,
+            ">=" => Comparison::Ge,
+            _ => bail!("Unknown comparison `{}`", s),
+        })
+    }
+
+    fn holds<T: FungeInteger>(&self, a: &T, b: &T) -> bool {
+        match self {
+            Comparison::Eq => a == b,
+            Comparison::Ne => a != b,
+            Comparison::Lt => a < b,
+            Comparison::Le => a <= b,
+            Comparison::Gt => a > b,
+            Comparison::Ge => a >= b,
+        }
+    }
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            Comparison::Eq => "==",
+            Comparison::Ne => "!=",
+            Comparison::Lt => "<",
+            Comparison::Le => "<=",
+            Comparison::Gt => ">",
+            Comparison::Ge => ">=",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// A condition that stops the execution of the program.
+enum Breakpoint<T: FungeInteger> {
+    /// Before an IP executes the cell at the given position
+    Cell((T, T, T)),
+    /// Before an IP executes the given instruction
+    Instruction(T),
+    /// Before an IP executes anything, while the depth of its TOSS compares so to the value
+    StackDepth(Comparison, T),
+    /// Before an IP executes anything, while the top of its TOSS compares so to the value
+    StackTop(Comparison, T),
+    /// After the cell at the given position is written to
+    Watch((T, T, T)),
+}
+
+impl<T: FungeInteger> Breakpoint<T> {
+    fn kind(&self) -> &'static str {
+        match self {
+            Breakpoint::Watch(_) => "Watchpoint",
+            _ => "Breakpoint",
+        }
+    }
+}
+
+/// An interactive debugger, which drives a `Program` one step at a time.
+struct Debugger<T: FungeInteger> {
+    program: Program<T>,
+    /// The breakpoints and the watchpoints; the ID of each one is its index plus 1
+    breakpoints: Vec<Option<Breakpoint<T>>>,
+    /// Whether the program terminated (or failed), so it can not be executed any further
+    terminated: bool,
+}
+
+pub fn start<T: FungeInteger>(program: Program<T>) -> Result<()> {
+    println!("{} - Funge 98 debugger", env!("CARGO_PKG_NAME"));
+    println!("version {}", env!("CARGO_PKG_VERSION"));
+    println!("(type \"help\" for the list of commands, \"quit\" to quit)");
+
+    let mut debugger = Debugger {
+        program,
+        breakpoints: Vec::new(),
+        terminated: false,
+    };
+    debugger.show_location();
+
+    loop {
+        print!("{}", PROMPT);
+        io::stdout()
+            .flush()
+            .context("Failed to write a debugger prompt to stdout")?;
+
+        let line = match io::stdin().lock().lines().next() {
+            Some(line) => line.context("Failed to read line from stdin")?,
+            // the end of input quits the debugger
+            None => break,
+        };
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match words.first() {
+            None => continue,
+            Some(&("quit" | "q" | "exit")) => break,
+            Some(_) => {
+                if let Err(e) = debugger.execute(&words) {
+                    println!("error: {}", e);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+impl<T: FungeInteger> Debugger<T> {
+    /// Executes the debugger command `words`.
+    fn execute(&mut self, words: &[&str]) -> Result<()> {
+        match words {
+            ["step" | "s"] => self.step(1),
+            ["step" | "s", n] => self.step(n.parse().context("Invalid number of steps")?),
+            ["continue" | "c"] => self.r#continue(),
+            ["break" | "b", "cell", vector @ ..] => {
+                let position = self.parse_vector(vector)?;
+                self.add_breakpoint(Breakpoint::Cell(position));
+                Ok(())
+            }
+            ["break" | "b", "instruction", instruction] => {
+                let instruction = parse_value(instruction)?;
+                self.add_breakpoint(Breakpoint::Instruction(instruction));
+                Ok(())
+            }
+            ["break" | "b", "stack", property, comparison, value] => {
+                let comparison = Comparison::parse(comparison)?;
+                let value = parse_value(value)?;
+                self.add_breakpoint(match *property {
+                    "depth" => Breakpoint::StackDepth(comparison, value),
+                    "top" => Breakpoint::StackTop(comparison, value),
+                    _ => bail!("Unknown stack property `{}`", property),
+                });
+                Ok(())
+            }
+            ["watch" | "w", vector @ ..] => {
+                let position = self.parse_vector(vector)?;
+                self.add_breakpoint(Breakpoint::Watch(position));
+                Ok(())
+            }
+            ["breakpoints" | "info"] => {
+                for (i, breakpoint) in self.breakpoints.iter().enumerate() {
+                    if let Some(breakpoint) = breakpoint {
+                        println!(
+                            "{} {}: {}",
+                            breakpoint.kind(),
+                            i + 1,
+                            self.describe(breakpoint)
+                        );
+                    }
+                }
+                Ok(())
+            }
+            ["delete" | "d", n] => {
+                let n: usize = n.parse().context("Invalid breakpoint number")?;
+                match self.breakpoints.get_mut(n.wrapping_sub(1)) {
+                    Some(breakpoint @ Some(_)) => {
+                        *breakpoint = None;
+                        Ok(())
+                    }
+                    _ => bail!("There is no breakpoint {}", n),
+                }
+            }
+            ["ip"] => {
+                self.show_ips();
+                Ok(())
+            }
+            ["grid" | "g"] => self.show_grid(5),
+            ["grid" | "g", radius] => self.show_grid(radius.parse().context("Invalid radius")?),
+            ["stack"] => {
+                let ip = self.program.current_ip().ok_or_else(no_ip)?;
+                println!("TOSS: {}", format_stack(ip.toss()));
+                if let Some(soss) = ip.soss() {
+                    println!("SOSS: {}", format_stack(soss));
+                }
+                Ok(())
+            }
+            ["stacks"] => {
+                let ip = self.program.current_ip().ok_or_else(no_ip)?;
+                for (i, stack) in ip.stacks().iter().rev().enumerate() {
+                    println!("{}: {}", i, format_stack(stack));
+                }
+                Ok(())
+            }
+            ["set", "cell", vector @ .., value] => {
+                let position = self.parse_vector(vector)?;
+                let value = parse_value(value)?;
+                self.program.set_cell(position, value);
+                Ok(())
+            }
+            ["set", "stack", n, value] => {
+                let n: usize = n.parse().context("Invalid stack index")?;
+                let value = parse_value(value)?;
+                let toss = self.toss_mut()?;
+                let len = toss.len();
+                if n >= len {
+                    bail!("The TOSS has only {} values", len);
+                }
+                toss[len - n - 1] = value;
+                Ok(())
+            }
+            ["push", value] => {
+                let value = parse_value(value)?;
+                self.toss_mut()?.push(value);
+                Ok(())
+            }
+            ["pop"] => {
+                let value = self.toss_mut()?.pop().unwrap_or_default();
+                println!("{}", value);
+                Ok(())
+            }
+            ["help" | "h"] => {
+                println!("{}", HELP);
+                Ok(())
+            }
+            _ => bail!("Unknown command; type \"help\" for the list of commands"),
+        }
+    }
+
+    /// Executes the next `n` instructions, unless a watchpoint stops the execution earlier.
+    fn step(&mut self, n: usize) -> Result<()> {
+        for _ in 0..n {
+            if !self.execute_instruction()? {
+                return Ok(());
+            }
+        }
+        self.show_location();
+        Ok(())
+    }
+
+    /// Executes instructions until a breakpoint or a watchpoint stops the execution.
+    fn r#continue(&mut self) -> Result<()> {
+        // the breakpoint the execution stopped at (if any) must not stop it again
+        if !self.execute_instruction()? {
+            return Ok(());
+        }
+        loop {
+            if let Some(id) = self.hit_breakpoint() {
+                println!("Breakpoint {}", id);
+                self.show_location();
+                return Ok(());
+            }
+            if !self.execute_instruction()? {
+                return Ok(());
+            }
+        }
+    }
+
+    /**
+    Executes a single instruction and returns whether the execution can go on
+    i.e., the program did not terminate and no watchpoint was hit.
+    */
+    fn execute_instruction(&mut self) -> Result<bool> {
+        if self.terminated {
+            bail!("The program is not running");
+        }
+        let step = match self.program.step() {
+            Ok(step) => step,
+            Err(e) => {
+                self.terminated = true;
+                return Err(e);
+            }
+        };
+        if let Some(exit) = step.exit {
+            self.terminated = true;
+            match exit {
+                Exit::Ended => println!("\nThe program ended"),
+                Exit::Quit(code) => println!("\nThe program quit with exit code {}", code),
+            }
+            return Ok(false);
+        }
+
+        let mut watched = false;
+        for (i, breakpoint) in self.breakpoints.iter().enumerate() {
+            if let Some(Breakpoint::Watch(position)) = breakpoint {
+                if step.written.contains(position) {
+                    println!(
+                        "Watchpoint {}: {} is now {}",
+                        i + 1,
+                        self.format_vector(position),
+                        format_value(&self.program.cell(position.clone()))
+                    );
+                    watched = true;
+                }
+            }
+        }
+        if watched {
+            self.show_location();
+        }
+        Ok(!watched)
+    }
+
+    /// Returns the ID of the first breakpoint that stops the IP that executes next, if any.
+    fn hit_breakpoint(&self) -> Option<usize> {
+        let ip = self.program.current_ip()?;
+        let position = ip.position();
+        let toss = ip.toss();
+        let depth = T::from(toss.len()).unwrap_or_default();
+        let top = toss.last().cloned().unwrap_or_default();
+
+        self.breakpoints
+            .iter()
+            .position(|breakpoint| match breakpoint {
+                Some(Breakpoint::Cell(p)) => *p == position,
+                Some(Breakpoint::Instruction(i)) => {
+                    !ip.string_mode() && *i == self.program.cell(position.clone())
+                }
+                Some(Breakpoint::StackDepth(comparison, value)) => comparison.holds(&depth, value),
+                Some(Breakpoint::StackTop(comparison, value)) => comparison.holds(&top, value),
+                Some(Breakpoint::Watch(_)) | None => false,
+            })
+            .map(|i| i + 1)
+    }
+
+    fn add_breakpoint(&mut self, breakpoint: Breakpoint<T>) {
+        println!(
+            "{} {}: {}",
+            breakpoint.kind(),
+            self.breakpoints.len() + 1,
+            self.describe(&breakpoint)
+        );
+        self.breakpoints.push(Some(breakpoint));
+    }
+
+    fn describe(&self, breakpoint: &Breakpoint<T>) -> String {
+        match breakpoint {
+            Breakpoint::Cell(position) => format!("cell {}", self.format_vector(position)),
+            Breakpoint::Instruction(i) => format!("instruction {}", format_value(i)),
+            Breakpoint::StackDepth(comparison, value) => {
+                format!("stack depth {} {}", comparison, value)
+            }
+            Breakpoint::StackTop(comparison, value) => {
+                format!("stack top {} {}", comparison, format_value(value))
+            }
+            Breakpoint::Watch(position) => format!("cell {}", self.format_vector(position)),
+        }
+    }
+
+    /// Shows the IP that executes next and the instruction it is about to execute.
+    fn show_location(&self) {
+        if let Some(ip) = self.program.current_ip() {
+            println!(
+                "IP {} at {}: {}{}",
+                ip.id(),
+                self.format_vector(&ip.position()),
+                format_value(&self.program.cell(ip.position())),
+                if ip.string_mode() {
+                    " (string mode)"
+                } else {
+                    ""
+                }
+            );
+        }
+    }
+
+    fn show_ips(&self) {
+        for ip in self.program.ips() {
+            println!(
+                "IP {}: position {}, delta {}, storage offset {}{}",
+                ip.id(),
+                self.format_vector(&ip.position()),
+                self.format_vector(&ip.delta()),
+                self.format_vector(&ip.storage_offset()),
+                if ip.string_mode() {
+                    ", string mode"
+                } else {
+                    ""
+                }
+            );
+        }
+    }
+
+    /// Shows the cells up to `radius` cells around the IP that executes next, on its plane.
+    fn show_grid(&self, radius: usize) -> Result<()> {
+        let ip = self.program.current_ip().ok_or_else(no_ip)?;
+        let (x, y, z) = ip.position();
+        let rows: Vec<T> = around(&y, radius).ok_or_else(|| anyhow!("Invalid radius"))?;
+        let columns: Vec<T> = around(&x, radius).ok_or_else(|| anyhow!("Invalid radius"))?;
+        for row in rows {
+            let cells: String = columns
+                .iter()
+                .map(|column| {
+                    let c = self.program.cell((column.clone(), row.clone(), z.clone()));
+                    match c.to_u8() {
+                        Some(b) if (b' '..=b'~').contains(&b) => b as char,
+                        _ => '\u{a4}',
+                    }
+                })
+                .collect();
+            println!("{:>6} {}", row, cells);
+            if row == y {
+                let offset = columns.iter().take_while(|column| **column != x).count();
+                println!("{:>6} {}^", "", " ".repeat(offset));
+            }
+        }
+        Ok(())
+    }
+
+    fn toss_mut(&mut self) -> Result<&mut Vec<T>> {
+        let stacks = self.program.current_stacks_mut().ok_or_else(no_ip)?;
+        if stacks.is_empty() {
+            stacks.push(Vec::new());
+        }
+        Ok(stacks.last_mut().unwrap())
+    }
+
+    /// Parses the components of a vector; the missing ones (up to three) are 0.
+    fn parse_vector(&self, words: &[&str]) -> Result<(T, T, T)> {
+        let dimensions = self.program.dialect().dimensions();
+        if words.is_empty() || words.len() > dimensions {
+            bail!("A vector has 1 to {} components", dimensions);
+        }
+        let mut components = words.iter().map(|word| parse_value::<T>(word));
+        let mut next = || components.next().unwrap_or_else(|| Ok(T::zero()));
+        Ok((next()?, next()?, next()?))
+    }
+
+    /// Formats a vector, with one component per dimension of the program.
+    fn format_vector(&self, vector: &(T, T, T)) -> String {
+        bff::format_vector(vector, self.program.dialect().dimensions())
+    }
+}
+
+/// Parses a value: a number or a character preceded by a single quote, like `'A`.
+fn parse_value<T: FungeInteger>(word: &str) -> Result<T> {
+    let mut chars = word.chars();
+    match (chars.next(), chars.next(), chars.next()) {
+        (Some('\''), Some(c), None) => T::from(c as u32),
+        // a single character that is not a digit is an instruction
+        (Some(c), None, None) if !c.is_ascii_digit() => T::from(c as u32),
+        _ => T::from_str_radix(word, 10).ok(),
+    }
+    .ok_or_else(|| anyhow!("Invalid value `{}`", word))
+}
+
+/// Formats a stack, bottom first.
+fn format_stack<T: FungeInteger>(stack: &[T]) -> String {
+    let values: Vec<String> = stack.iter().map(|value| value.to_string()).collect();
+    format!("[{}]", values.join(" "))
+}
+
+/// Returns the values up to `radius` away from `centre`, wrapping around the limits of a cell.
+fn around<T: FungeInteger>(centre: &T, radius: usize) -> Option<Vec<T>> {
+    let mut value = centre.overflowing_sub(&T::from(radius)?).0;
+    let mut values = Vec::new();
+    for _ in 0..=radius.checked_mul(2)? {
+        values.push(value.clone());
+        value = value.overflowing_add(&T::one()).0;
+    }
+    Some(values)
+}
+
+fn no_ip() -> anyhow::Error {
+    anyhow!("The program is not running")
+}
@@ -0,0 +1,33 @@
+/*!
+`bff` (*Befunge Forever*) is an interpreter of Funge-98 (Unefunge, Befunge and Trefunge)
+and of Befunge-93 programs, which can be embedded in other programs.
+
+A `Program` is built out of its source code with a `ProgramBuilder`, which also selects
+its dialect and the streams it reads from and writes to; the type of its cells is the type
+parameter of the program (`i32`, `i64`, `i128` or `BigInt`). The program can then be run
+to completion, for a number of ticks at a time or a single instruction at a time (with `step`),
+while its state is inspected through its read-only accessors:
+
+```
+use bff::{Dialect, Exit, Program};
+
+let mut program = Program::<i64>::builder("&:*.7q")
+    .dialect(Dialect::Unefunge)
+    .stdin(&b"12\n"[..])
+    .stdout(std::io::sink())
+    .build();
+
+assert_eq!(program.run_for(1)?, None);
+assert_eq!(program.run()?, Exit::Quit(7));
+# Ok::<(), anyhow::Error>(())
+```
+*/
+
+mod program;
+
+pub use chrono::{DateTime, Utc};
+pub use num_bigint::BigInt;
+pub use program::{
+    format_value, format_vector, fungetypes::FungeInteger, Clock, Dialect, Engine, Exit, IpState,
+    Paradigm, Profile, Program, ProgramBuilder, Step, Storage, SystemClock, Trace,
+};
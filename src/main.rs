@@ -1,27 +1,181 @@
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
+use bff::{BigInt, Dialect, FungeInteger, Profile, Program, ProgramBuilder, Trace};
 use clap::Parser;
+use std::{
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::Path,
+};
 
-use program::Program;
+use args::{Args, CellSize, Command, Standard};
+use stderr::Stderr;
 
 mod args;
-mod program;
+mod debugger;
 mod repl;
+mod stderr;
+mod visualiser;
 
 fn main() -> Result<()> {
-    let args = args::Args::parse();
-    if args.file.is_none() && args.ucode.is_none() {
-        repl::start()
+    let args = Args::parse();
+    match args.cell_size {
+        CellSize::I32 => run::<i32>(args),
+        CellSize::I64 => run::<i64>(args),
+        CellSize::I128 => run::<i128>(args),
+        CellSize::Bignum => run::<BigInt>(args),
+    }
+}
+
+/// Starts the REPL, the debugger or the visualiser, compiles the given program or runs it, with cells of type `T`.
+fn run<T: FungeInteger>(args: Args) -> Result<()> {
+    if let Some(Command::Debug { file }) = &args.command {
+        let program = builder::<T>(&args, file)?
+            .paradigm(args.paradigm.into())
+            .storage(args.storage.into())
+            .engine(args.engine.into())
+            .build();
+        debugger::start(program)
+    } else if let Some(Command::Visualise { file }) = &args.command {
+        visualiser::start(
+            builder::<T>(&args, file)?
+                .paradigm(args.paradigm.into())
+                .storage(args.storage.into())
+                .engine(args.engine.into()),
+        )
+    } else if let Some(Command::Compile { file, output }) = &args.command {
+        let code = builder::<T>(&args, file)?.build().compile()?;
+        match output {
+            Some(path) => fs::write(path, code).context("Failed to write the compiled program")?,
+            None => io::stdout()
+                .write_all(code.as_bytes())
+                .context("Failed to write the compiled program to stdout")?,
+        }
+        Ok(())
+    } else if args.file.is_none() && args.ucode.is_none() {
+        repl::start::<T>()
     } else {
-        let mut program = match args.file {
-            Some(filepath) => Program::<i32>::try_from(filepath)?,
-            None => {
-                // unwrap is safe here
-                // args.ucode is a "Some" for sure at this point
-                let unefunge_code = args.ucode.unwrap().bytes().collect();
-                Program::<i32>::from(vec![unefunge_code])
+        let builder = match &args.file {
+            Some(filepath) => builder(&args, filepath)?,
+            None if args.trefunge => {
+                bail!("`-u` programs are Unefunge programs; `--trefunge` requires a source file")
             }
+            None if args.standard == Some(Standard::Befunge93) => {
+                bail!("There is no Unefunge 93; `-u` programs are always Unefunge 98 programs")
+            }
+            // unwrap is safe here
+            // args.ucode is a "Some" for sure at this point
+            None => Program::<T>::builder(args.ucode.as_ref().unwrap())
+                .dialect(Dialect::Unefunge)
+                .stderr(Stderr::default()),
+        };
+        let builder = match trace(&args)? {
+            Some(trace) => builder.trace(trace),
+            None => builder,
+        };
+        let builder = if args.profile {
+            builder.profile()
+        } else {
+            builder
         };
-        let exit_code = program.run().context("Runtime error")?;
-        std::process::exit(exit_code);
+        let mut program = builder
+            .paradigm(args.paradigm.into())
+            .storage(args.storage.into())
+            .engine(args.engine.into())
+            .build();
+        let result = program.run();
+        // a profile is reported even if the program failed, up to the point it did
+        if let Some(profile) = program.profile() {
+            report(&args, profile)?;
+        }
+        // the trace is flushed when the program is dropped, which `exit` would not do
+        drop(program);
+        std::process::exit(result?.code());
+    }
+}
+
+/// Reads the source file `filepath` and starts building a program out of it.
+fn builder<T: FungeInteger>(args: &Args, filepath: &Path) -> Result<ProgramBuilder<T>> {
+    let source = fs::read(filepath).context("Failed to read Befunge source file")?;
+    Ok(Program::builder(source)
+        .dialect(dialect(args, filepath)?)
+        .stderr(Stderr::default()))
+}
+
+/**
+Returns the dialect to run the source file `filepath` in: the one the options select
+or, in their absence, the one the extension of the file suggests.
+*/
+fn dialect(args: &Args, filepath: &Path) -> Result<Dialect> {
+    Ok(match (args.standard, args.trefunge) {
+        (Some(Standard::Befunge93), true) => {
+            bail!("There is no Trefunge 93; `--trefunge` requires `--std=98`")
+        }
+        (Some(Standard::Befunge93), false) => Dialect::Befunge93,
+        (_, true) => Dialect::Trefunge,
+        (standard, false) => match Dialect::from_path(filepath) {
+            Dialect::Befunge93 if standard == Some(Standard::Funge98) => Dialect::Befunge,
+            dialect => dialect,
+        },
+    })
+}
+
+/// Returns the trace that the options ask for, if they ask for one.
+fn trace<T: FungeInteger>(args: &Args) -> Result<Option<Trace<T>>> {
+    if !args.trace {
+        return Ok(None);
+    }
+    let output: Box<dyn Write> = match &args.trace_file {
+        Some(path) => Box::new(File::create(path).context("Failed to create the trace file")?),
+        None => Box::new(io::stderr()),
+    };
+    let mut trace = Trace::new(BufWriter::new(output));
+    if let Some(top) = args.trace_top {
+        trace = trace.top(top);
+    }
+    if let Some(region) = &args.trace_region {
+        let (from, to) = region
+            .split_once(':')
+            .context("A region is given as FROM:TO, like `0,0:79,24`")?;
+        trace = trace.region(parse_vector(from)?, parse_vector(to)?);
+    }
+    if let Some(instructions) = &args.trace_instructions {
+        trace = trace.instructions(instructions);
+    }
+    Ok(Some(trace))
+}
+
+/// Parses a vector given as its comma-separated components; the missing ones (up to three) are 0.
+fn parse_vector<T: FungeInteger>(s: &str) -> Result<(T, T, T)> {
+    let components = s
+        .split(',')
+        .map(|c| T::from_str_radix(c.trim(), 10).ok())
+        .collect::<Option<Vec<T>>>()
+        .filter(|components| components.len() <= 3)
+        .with_context(|| format!("Invalid vector `{}`", s))?;
+    let mut components = components.into_iter();
+    let mut next = || components.next().unwrap_or_else(T::zero);
+    Ok((next(), next(), next()))
+}
+
+/// Writes the summary of `profile` to stderr and its matrices to the files the options ask for.
+fn report<T: FungeInteger>(args: &Args, profile: &Profile<T>) -> Result<()> {
+    profile
+        .write_summary(&mut io::stderr().lock())
+        .context("Failed to write the profile to stderr")?;
+    if let Some(path) = &args.profile_csv {
+        let mut file = BufWriter::new(File::create(path).context("Failed to create the CSV file")?);
+        profile
+            .write_csv(&mut file)
+            .and_then(|_| file.flush())
+            .context("Failed to write the CSV file")?;
+    }
+    if let Some(path) = &args.profile_json {
+        let mut file =
+            BufWriter::new(File::create(path).context("Failed to create the JSON file")?);
+        profile
+            .write_json(&mut file)
+            .and_then(|_| file.flush())
+            .context("Failed to write the JSON file")?;
     }
+    Ok(())
 }
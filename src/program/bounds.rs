@@ -1,33 +1,55 @@
+use num_bigint::BigInt;
+
 use super::fungetypes::FungeInteger;
 
-#[derive(Default)]
+/**
+The least and the greatest point of Funge-Space, both inclusive, so that Funge-Space
+can reach the greatest coordinate of a cell; while Funge-Space is empty on an axis,
+its greatest coordinate on that axis lies right before its least one.
+*/
 pub struct Bounds<T> {
-    lower: (T, T),
-    upper: (T, T),
+    lower: (T, T, T),
+    upper: (T, T, T),
 }
 
-impl<T: FungeInteger> Bounds<T> {
-    pub fn new(x_lower: T, y_lower: T, x_upper: T, y_upper: T) -> Self {
+impl<T: FungeInteger> Default for Bounds<T> {
+    /// Empty bounds, right after the origin.
+    fn default() -> Self {
+        let before = -T::one();
         Self {
-            lower: (x_lower, y_lower),
-            upper: (x_upper, y_upper),
+            lower: (T::zero(), T::zero(), T::zero()),
+            upper: (before.clone(), before.clone(), before),
         }
     }
+}
+
+impl<T: FungeInteger> Bounds<T> {
+    pub fn new(lower: (T, T, T), upper: (T, T, T)) -> Self {
+        Self { lower, upper }
+    }
 
     pub fn lower_x(&self) -> T {
-        self.lower.0
+        self.lower.0.clone()
     }
 
     pub fn lower_y(&self) -> T {
-        self.lower.1
+        self.lower.1.clone()
+    }
+
+    pub fn lower_z(&self) -> T {
+        self.lower.2.clone()
     }
 
     pub fn upper_x(&self) -> T {
-        self.upper.0
+        self.upper.0.clone()
     }
 
     pub fn upper_y(&self) -> T {
-        self.upper.1
+        self.upper.1.clone()
+    }
+
+    pub fn upper_z(&self) -> T {
+        self.upper.2.clone()
     }
 
     pub fn set_lower_x(&mut self, x: T) {
@@ -38,6 +60,10 @@ impl<T: FungeInteger> Bounds<T> {
         self.lower.1 = y;
     }
 
+    pub fn set_lower_z(&mut self, z: T) {
+        self.lower.2 = z;
+    }
+
     pub fn set_upper_x(&mut self, x: T) {
         self.upper.0 = x;
     }
@@ -46,8 +72,136 @@ impl<T: FungeInteger> Bounds<T> {
         self.upper.1 = y;
     }
 
-    /// Returns `true` if `(x, y)` coordinates are out of current bounds.
-    pub fn out_of_bounds(&self, (x, y): (T, T)) -> bool {
-        x < self.lower_x() || x >= self.upper_x() || y < self.lower_y() || y >= self.upper_y()
+    pub fn set_upper_z(&mut self, z: T) {
+        self.upper.2 = z;
+    }
+
+    /// Returns `true` if `(x, y, z)` coordinates are out of current bounds.
+    pub fn out_of_bounds(&self, (x, y, z): (T, T, T)) -> bool {
+        x < self.lower.0
+            || x > self.upper.0
+            || y < self.lower.1
+            || y > self.upper.1
+            || z < self.lower.2
+            || z > self.upper.2
+    }
+
+    /**
+    Returns the position one step away from `position`, on the direction of `delta`.
+
+    If the step leaves the bounds, it wraps around (like in Lahey-space): the position
+    is the farthest one within the bounds on the opposite direction, which is found
+    at once, instead of walking back across Funge-Space one step at a time.
+    A `position` that is already out of bounds just takes the step.
+    */
+    pub fn step(&self, position: (T, T, T), delta: &(T, T, T)) -> (T, T, T) {
+        let (x, y, z) = position;
+        let (next_x, overflowed_x) = x.overflowing_add(&delta.0);
+        let (next_y, overflowed_y) = y.overflowing_add(&delta.1);
+        let (next_z, overflowed_z) = z.overflowing_add(&delta.2);
+        let next = (next_x, next_y, next_z);
+        let overflowed = overflowed_x || overflowed_y || overflowed_z;
+        if self.out_of_bounds((x.clone(), y.clone(), z.clone()))
+            || !(overflowed || self.out_of_bounds(next.clone()))
+        {
+            return next;
+        }
+        let position = [x, y, z];
+        let delta = [delta.0.clone(), delta.1.clone(), delta.2.clone()];
+        let lower = [
+            self.lower.0.clone(),
+            self.lower.1.clone(),
+            self.lower.2.clone(),
+        ];
+        let upper = [
+            self.upper.0.clone(),
+            self.upper.1.clone(),
+            self.upper.2.clone(),
+        ];
+        // bounds that span more than the greatest cell are walked back in unbounded integers
+        let [x, y, z] = wrap_back(&position, &delta, &lower, &upper).unwrap_or_else(|| {
+            let big = |v: &[T; 3]| {
+                v.clone()
+                    .map(|c| <BigInt as FungeInteger>::from(c).unwrap_or_default())
+            };
+            wrap_back(&big(&position), &big(&delta), &big(&lower), &big(&upper))
+                .unwrap_or_default()
+                .map(|c| T::from(c).unwrap_or_default())
+        });
+        (x, y, z)
+    }
+}
+
+/**
+Moves `position` back by `delta` as many times as it can without leaving the box
+from `lower` to `upper`, on the axis that runs out of the box first;
+returns `None` if the distances to the edges of the box do not fit in a cell.
+*/
+fn wrap_back<N: FungeInteger>(
+    position: &[N; 3],
+    delta: &[N; 3],
+    lower: &[N; 3],
+    upper: &[N; 3],
+) -> Option<[N; 3]> {
+    let mut steps: Option<N> = None;
+    for i in 0..3 {
+        let (c, d) = (&position[i], &delta[i]);
+        let axis_steps = if *d > N::zero() {
+            let (distance, overflowed) = c.overflowing_sub(&lower[i]);
+            if overflowed {
+                return None;
+            }
+            distance / d.clone()
+        } else if *d < N::zero() {
+            let (distance, overflowed) = upper[i].overflowing_sub(c);
+            if overflowed {
+                return None;
+            }
+            // rounds towards 0, like the division by a positive `d` does
+            N::zero() - distance / d.clone()
+        } else {
+            continue;
+        };
+        steps = Some(match steps {
+            Some(steps) => steps.min(axis_steps),
+            None => axis_steps,
+        });
+    }
+    let steps = steps.unwrap_or_default();
+    Some([0, 1, 2].map(|i| position[i].clone() - steps.clone() * delta[i].clone()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_step() {
+        let bounds = Bounds::new((0, 0, 0), (9, 3, 0));
+        assert_eq!(bounds.step((3, 2, 0), &(1, 0, 0)), (4, 2, 0));
+        assert_eq!(bounds.step((9, 2, 0), &(1, 0, 0)), (0, 2, 0));
+        assert_eq!(bounds.step((0, 2, 0), &(-1, 0, 0)), (9, 2, 0));
+        assert_eq!(bounds.step((5, 0, 0), &(0, -1, 0)), (5, 3, 0));
+        assert_eq!(bounds.step((8, 3, 0), &(2, 1, 0)), (2, 0, 0));
+        assert_eq!(bounds.step((7, 1, 0), &(-3, 0, 0)), (4, 1, 0));
+        assert_eq!(bounds.step((1, 1, 0), &(-3, 0, 0)), (7, 1, 0));
+        assert_eq!(bounds.step((12, 1, 0), &(1, 0, 0)), (13, 1, 0));
+        assert_eq!(bounds.step((5, 2, 0), &(0, 0, 1)), (5, 2, 0));
+    }
+
+    #[test]
+    fn test_step_at_the_limits_of_a_cell() {
+        let bounds = Bounds::new((-5, 0, 0), (i32::MAX, 0, 0));
+        assert_eq!(bounds.step((i32::MAX, 0, 0), &(1, 0, 0)), (-5, 0, 0));
+        assert_eq!(bounds.step((-5, 0, 0), &(-1, 0, 0)), (i32::MAX, 0, 0));
+        // bounds that span more than the greatest cell
+        let bounds = Bounds::new((i32::MIN, i32::MIN, 0), (i32::MAX, i32::MAX, 0));
+        assert_eq!(bounds.step((i32::MAX, 0, 0), &(1, 0, 0)), (i32::MIN, 0, 0));
+        assert_eq!(bounds.step((i32::MIN, 0, 0), &(-1, 0, 0)), (i32::MAX, 0, 0));
+        assert_eq!(
+            bounds.step((i32::MAX - 1, 0, 0), &(3, 0, 0)),
+            (i32::MIN + 2, 0, 0)
+        );
+        assert_eq!(bounds.step((0, i32::MAX, 0), &(1, 1, 0)), (i32::MIN, -1, 0));
     }
 }
@@ -0,0 +1,128 @@
+use std::{
+    io::{Read, Write},
+    marker::PhantomData,
+    rc::Rc,
+};
+
+use super::{
+    clock::{Clock, SystemClock},
+    fungetypes::FungeInteger,
+    runs::RunCache,
+    streams::Streams,
+    Dialect, Engine, Paradigm, Profile, Program, Storage, Trace,
+};
+
+/**
+A builder of a `Program`, for embedding the interpreter.
+
+Unless told otherwise, the program is a Befunge-98 program with `=` disabled,
+which reads from and writes to the standard streams of the process
+and reads the current time from the clock of the operating system.
+*/
+pub struct ProgramBuilder<T: FungeInteger> {
+    source: Vec<u8>,
+    dialect: Dialect,
+    paradigm: Paradigm,
+    storage: Storage,
+    engine: Engine,
+    streams: Streams,
+    clock: Rc<dyn Clock>,
+    trace: Option<Trace<T>>,
+    profile: bool,
+    cells: PhantomData<T>,
+}
+
+impl<T: FungeInteger> ProgramBuilder<T> {
+    /// Starts building a program out of its `source` code, either a string or raw bytes.
+    pub fn new(source: impl AsRef<[u8]>) -> Self {
+        Self {
+            source: source.as_ref().to_vec(),
+            dialect: Dialect::default(),
+            paradigm: Paradigm::default(),
+            storage: Storage::default(),
+            engine: Engine::default(),
+            streams: Streams::default(),
+            clock: Rc::new(SystemClock),
+            trace: None,
+            profile: false,
+            cells: PhantomData,
+        }
+    }
+
+    /// Sets the dialect the source code is written in.
+    pub fn dialect(mut self, dialect: Dialect) -> Self {
+        self.dialect = dialect;
+        self
+    }
+
+    /// Sets the operating paradigm of the `=` (execute) instruction.
+    pub fn paradigm(mut self, paradigm: Paradigm) -> Self {
+        self.paradigm = paradigm;
+        self
+    }
+
+    /// Sets the way Funge-Space is stored; see `Storage`.
+    pub fn storage(mut self, storage: Storage) -> Self {
+        self.storage = storage;
+        self
+    }
+
+    /// Sets the engine that executes the program; see `Engine`.
+    pub fn engine(mut self, engine: Engine) -> Self {
+        self.engine = engine;
+        self
+    }
+
+    /// Sets the stream that `&`, `~` and the fingerprints read input from.
+    pub fn stdin(mut self, stdin: impl Read + 'static) -> Self {
+        self.streams.stdin = Box::new(stdin);
+        self
+    }
+
+    /// Sets the stream that `.`, `,` and the fingerprints write output to.
+    pub fn stdout(mut self, stdout: impl Write + 'static) -> Self {
+        self.streams.stdout = Box::new(stdout);
+        self
+    }
+
+    /// Sets the stream that warnings (e.g. about overflows) are written to.
+    pub fn stderr(mut self, stderr: impl Write + 'static) -> Self {
+        self.streams.stderr = Box::new(stderr);
+        self
+    }
+
+    /// Sets the clock that `y` and the `HRTI` fingerprint read the current time from.
+    pub fn clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Rc::new(clock);
+        self
+    }
+
+    /// Traces the execution of the program; see `Trace`.
+    pub fn trace(mut self, trace: Trace<T>) -> Self {
+        self.trace = Some(trace);
+        self
+    }
+
+    /// Profiles the execution of the program; see `Program::profile`.
+    pub fn profile(mut self) -> Self {
+        self.profile = true;
+        self
+    }
+
+    /// Builds the program, ready to be run.
+    pub fn build(self) -> Program<T> {
+        let mut program = Program::from_source(&self.source, self.dialect);
+        program.paradigm = self.paradigm;
+        program.grid.set_storage(self.storage);
+        program.streams = self.streams;
+        program.clock = self.clock;
+        program.trace = self.trace;
+        if self.profile {
+            program.profile = Some(Profile::new(self.dialect.dimensions()));
+        }
+        if self.engine == Engine::Fast {
+            program.runs = Some(RunCache::default());
+        }
+        program
+    }
+}
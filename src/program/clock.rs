@@ -0,0 +1,29 @@
+use chrono::{DateTime, Utc};
+use std::rc::Rc;
+
+/**
+The source of the current time for everything time related in a program
+(e.g., `y` and the `HRTI` fingerprint), so that it can be replaced
+with a deterministic one (see `ProgramBuilder::clock`).
+*/
+pub trait Clock {
+    /// Returns the current date and time.
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// The clock of the operating system.
+#[derive(Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// A shared clock, so that whoever set it for a program can still move it.
+impl<C: Clock + ?Sized> Clock for Rc<C> {
+    fn now(&self) -> DateTime<Utc> {
+        (**self).now()
+    }
+}
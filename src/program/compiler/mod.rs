@@ -0,0 +1,478 @@
+use anyhow::{bail, Result};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Write,
+};
+
+use super::{fungetypes::FungeInteger, vm::BEFUNGE_93_INSTRUCTIONS, Dialect, Program};
+
+/// The runtime that every compiled program embeds; see the file itself.
+const RUNTIME: &str = include_str!("runtime.rs");
+/// The Funge-98 instructions that compiled programs do not support.
+const UNSUPPORTED_INSTRUCTIONS: &str = "kjx{}uy()tio=";
+/// The most instructions a block holds; a longer straight line is split in several blocks.
+const MAX_BLOCK_LENGTH: usize = 4096;
+
+/// The state of the IP that determines what it executes next.
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct State<T> {
+    position: (T, T, T),
+    delta: (T, T, T),
+    string_mode: bool,
+}
+
+/**
+Compiles a program to Rust, as a state machine of blocks: every block is a straight line
+of instructions that the IP executes from a given state on, which ends where control flow
+depends on the stack (`_`, `|`, `w`), on chance (`?`) or on another block.
+*/
+struct Compiler<'a, T: FungeInteger> {
+    program: &'a Program<T>,
+    /// The numbers of the blocks found so far, by the state they start from
+    blocks: HashMap<State<T>, usize>,
+    /// The blocks found but not compiled yet
+    pending: Vec<State<T>>,
+    /// The cells that the compiled code relies on
+    compiled: HashSet<(T, T, T)>,
+}
+
+impl<'a, T: FungeInteger> Compiler<'a, T> {
+    fn new(program: &'a Program<T>) -> Self {
+        Self {
+            program,
+            blocks: HashMap::new(),
+            pending: Vec::new(),
+            compiled: HashSet::new(),
+        }
+    }
+
+    /// Returns the number of the block that starts from `state`, finding it if it is new.
+    fn block(&mut self, state: State<T>) -> usize {
+        let next = self.blocks.len();
+        *self.blocks.entry(state.clone()).or_insert_with(|| {
+            self.pending.push(state);
+            next
+        })
+    }
+
+    fn step(&self, position: (T, T, T), delta: &(T, T, T)) -> (T, T, T) {
+        self.program.grid.get_bounds().step(position, delta)
+    }
+
+    /// Returns the value of the cell on `position`, recording that the compiled code relies on it.
+    fn read(&mut self, position: &(T, T, T)) -> T {
+        if !self.program.grid.out_of_bounds(position.clone()) {
+            self.compiled.insert(position.clone());
+        }
+        self.program.get_cell(position.clone())
+    }
+
+    /// Returns the instruction on `position`, recording that the compiled code relies on it.
+    fn read_char(&mut self, position: &(T, T, T)) -> char {
+        let c = self.read(position);
+        char::from_u32(c.to_u32().unwrap_or_default()).unwrap_or_default()
+    }
+
+    /// Compiles the block that starts from `start` to the body of a `match` arm.
+    fn compile_block(&mut self, start: State<T>) -> Result<String> {
+        let befunge_93 = self.program.dialect == Dialect::Befunge93;
+        let (zero, one) = (T::zero(), T::one());
+        let east = (one.clone(), zero.clone(), zero.clone());
+        let west = (-one.clone(), zero.clone(), zero.clone());
+        let south = (zero.clone(), one.clone(), zero.clone());
+        let north = (zero.clone(), -one, zero.clone());
+
+        let mut body = String::new();
+        let mut visited = HashSet::new();
+        let State {
+            mut position,
+            mut delta,
+            mut string_mode,
+        } = start;
+        loop {
+            let state = State {
+                position: position.clone(),
+                delta: delta.clone(),
+                string_mode,
+            };
+            // the straight line runs into another block, or into itself
+            if (!visited.is_empty() && self.blocks.contains_key(&state))
+                || visited.len() >= MAX_BLOCK_LENGTH
+                || !visited.insert(state.clone())
+            {
+                writeln!(body, "{}", self.block(state))?;
+                return Ok(body);
+            }
+
+            let x = self.read(&position);
+            let c = char::from_u32(x.to_u32().unwrap_or_default()).unwrap_or_default();
+            if string_mode {
+                match c {
+                    '"' => string_mode = false,
+                    // [SPEC] in Funge-98, consecutive spaces are pushed as a single one
+                    ' ' if !befunge_93 => {
+                        writeln!(body, "m.push(32);")?;
+                        loop {
+                            let next = self.step(position.clone(), &delta);
+                            if self.read_char(&next) != ' ' {
+                                break;
+                            }
+                            position = next;
+                        }
+                    }
+                    _ => writeln!(body, "m.push({});", x)?,
+                }
+                position = self.step(position, &delta);
+                continue;
+            }
+
+            let (px, py, _) = position.clone();
+            let (dx, dy, _) = delta.clone();
+            match c {
+                c if befunge_93 && !BEFUNGE_93_INSTRUCTIONS.contains(c) => delta = reflected(delta),
+                '0'..='9' | 'a'..='f' => {
+                    writeln!(body, "m.push({});", c.to_digit(16).unwrap_or_default())?
+                }
+                '+' => writeln!(body, "m.add();")?,
+                '-' => writeln!(body, "m.subtract();")?,
+                '*' => writeln!(body, "m.multiply();")?,
+                '/' => writeln!(body, "m.divide();")?,
+                '%' => writeln!(body, "m.remainder();")?,
+                '!' => writeln!(body, "m.not();")?,
+                '`' => writeln!(body, "m.greater();")?,
+                ':' => writeln!(body, "m.duplicate();")?,
+                '\\' => writeln!(body, "m.swap();")?,
+                '$' => writeln!(body, "m.discard();")?,
+                'n' => writeln!(body, "m.clear();")?,
+                '.' => writeln!(body, "m.print_integer();")?,
+                ',' => writeln!(body, "m.print_char();")?,
+                'g' => writeln!(body, "m.get_cell();")?,
+                '&' => writeln!(body, "m.read_integer_input();")?,
+                '~' => writeln!(body, "m.read_char_input();")?,
+                '>' => delta = east.clone(),
+                '<' => delta = west.clone(),
+                'v' => delta = south.clone(),
+                '^' => delta = north.clone(),
+                '[' => delta = (dy, -dx, zero.clone()),
+                ']' => delta = (-dy, dx, zero.clone()),
+                '"' => string_mode = true,
+                ' ' | 'z' => {}
+                '#' => position = self.step(position, &delta),
+                ';' => loop {
+                    position = self.step(position, &delta);
+                    if self.read_char(&position) == ';' {
+                        break;
+                    }
+                },
+                '\'' => {
+                    let fetched = (px + dx, py + dy, zero.clone());
+                    writeln!(body, "m.push({});", self.read(&fetched))?;
+                    position = self.step(position, &delta);
+                }
+                // the interpreter takes over as soon as the program modifies the compiled code
+                'p' => writeln!(
+                    body,
+                    "if m.put_cell() {{ return m.resume(({}, {}), ({}, {}), 1); }}",
+                    px, py, dx, dy
+                )?,
+                's' => {
+                    writeln!(
+                        body,
+                        "if m.store(({}, {})) {{ return m.resume(({}, {}), ({}, {}), 2); }}",
+                        px.clone() + dx.clone(),
+                        py.clone() + dy.clone(),
+                        px,
+                        py,
+                        dx,
+                        dy
+                    )?;
+                    position = self.step(position, &delta);
+                }
+                '@' => {
+                    writeln!(body, "return m.exit(0);")?;
+                    return Ok(body);
+                }
+                'q' => {
+                    writeln!(body, "return m.quit();")?;
+                    return Ok(body);
+                }
+                '_' | '|' | '?' | 'w' => {
+                    let deltas = match c {
+                        '_' => vec![east.clone(), west.clone()],
+                        '|' => vec![south.clone(), north.clone()],
+                        '?' => vec![east.clone(), west.clone(), south.clone(), north.clone()],
+                        // turn left, turn right or go on
+                        _ => vec![
+                            (dy.clone(), -dx.clone(), zero.clone()),
+                            (-dy, dx, zero.clone()),
+                            delta.clone(),
+                        ],
+                    };
+                    let targets: Vec<usize> = deltas
+                        .into_iter()
+                        .map(|delta| {
+                            let position = self.step(position.clone(), &delta);
+                            self.block(State {
+                                position,
+                                delta,
+                                string_mode,
+                            })
+                        })
+                        .collect();
+                    match c {
+                        '_' | '|' => writeln!(
+                            body,
+                            "if m.pop() == 0 {{ {} }} else {{ {} }}",
+                            targets[0], targets[1]
+                        )?,
+                        '?' => writeln!(
+                            body,
+                            "match m.random(4) {{ 0 => {}, 1 => {}, 2 => {}, _ => {} }}",
+                            targets[0], targets[1], targets[2], targets[3]
+                        )?,
+                        _ => writeln!(
+                            body,
+                            "let (b, a) = (m.pop(), m.pop());\n\
+                             if a < b {{ {} }} else if a > b {{ {} }} else {{ {} }}",
+                            targets[0], targets[1], targets[2]
+                        )?,
+                    }
+                    return Ok(body);
+                }
+                c if UNSUPPORTED_INSTRUCTIONS.contains(c) => bail!(
+                    "The `{}` instruction on ({}, {}) can not be compiled; run the program with bff instead",
+                    c,
+                    px,
+                    py
+                ),
+                // every other instruction reflects, including the fingerprint ones,
+                // since no fingerprint can be loaded
+                _ => delta = reflected(delta),
+            }
+            position = self.step(position, &delta);
+        }
+    }
+
+    /// Compiles every block that the IP can reach, from the start of the program on.
+    fn compile_blocks(&mut self) -> Result<Vec<(State<T>, String)>> {
+        let (zero, one) = (T::zero(), T::one());
+        self.block(State {
+            position: (zero.clone(), zero.clone(), zero.clone()),
+            delta: (one, zero.clone(), zero),
+            string_mode: false,
+        });
+        let mut blocks = Vec::new();
+        while let Some(state) = self.pending.pop() {
+            let body = self.compile_block(state.clone())?;
+            blocks.push((state, body));
+        }
+        blocks.sort_by_key(|(state, _)| self.blocks[state]);
+        Ok(blocks)
+    }
+}
+
+impl<T: FungeInteger> Program<T> {
+    /**
+    Compiles the program ahead of time, to the source code of a standalone Rust program,
+    which behaves like the program does when it runs in bff.
+
+    The control flow of the program is found from its initial Funge-Space on, and compiled
+    to straight-line blocks of native code; the compiled program embeds an interpreter as well,
+    which takes over once the program modifies a cell that the compiled code relies on
+    (or, in Funge-98, changes the bounds of Funge-Space) with `p` or `s`.
+
+    Only Befunge-93 and Befunge-98 programs with cells of up to 128 bits can be compiled;
+    in Befunge-98, the IP must not be able to reach `k`, `j` or `x`, which move it in ways
+    that are only known at run time, nor the instructions that deal with stack stacks,
+    concurrency, fingerprints, files and the system (`{`, `}`, `u`, `t`, `(`, `)`, `i`, `o`, `=` and `y`).
+    Fails if the program is not one of them or if it has already started running.
+    */
+    pub fn compile(&self) -> Result<String> {
+        let cell = match T::SIZE {
+            4 => "i32",
+            8 => "i64",
+            16 => "i128",
+            _ => bail!("Only programs with cells of up to 128 bits can be compiled"),
+        };
+        if !matches!(self.dialect, Dialect::Befunge | Dialect::Befunge93) {
+            bail!("Only Befunge-93 and Befunge-98 programs can be compiled");
+        }
+        if self.tick > 0 || self.ips.len() != 1 {
+            bail!("A program can only be compiled before it starts running");
+        }
+
+        let mut compiler = Compiler::new(self);
+        let blocks = compiler.compile_blocks()?;
+
+        let bounds = self.grid.get_bounds();
+        let width = bounds.upper_x().to_usize().map_or(0, |x| x + 1);
+        let height = bounds.upper_y().to_usize().map_or(0, |y| y + 1);
+        let mut code = String::new();
+        writeln!(
+            code,
+            "// Compiled by bff {}; build it with `rustc --edition 2021 -O`.\n",
+            env!("CARGO_PKG_VERSION")
+        )?;
+        // the parts of the runtime (or of the state machine) that a program does not need are left unused
+        writeln!(
+            code,
+            "#![allow(dead_code, unreachable_code, unused_mut, unused_variables)]\n"
+        )?;
+        writeln!(code, "type Cell = {};", cell)?;
+        writeln!(
+            code,
+            "const BEFUNGE_93: bool = {};",
+            self.dialect == Dialect::Befunge93
+        )?;
+        writeln!(
+            code,
+            "const BEFUNGE_93_INSTRUCTIONS: &str = {:?};",
+            BEFUNGE_93_INSTRUCTIONS
+        )?;
+        writeln!(
+            code,
+            "const UNSUPPORTED_INSTRUCTIONS: &str = {:?};",
+            UNSUPPORTED_INSTRUCTIONS
+        )?;
+        writeln!(code, "const WIDTH: usize = {};", width)?;
+        writeln!(code, "const HEIGHT: usize = {};\n", height)?;
+        writeln!(code, "/// The source code, row by row")?;
+        writeln!(code, "const SOURCE: [&[u8]; HEIGHT] = [")?;
+        for y in 0..height {
+            let row: Vec<u8> = (0..width)
+                .map(|x| {
+                    let c = self.get_cell(position(x, y));
+                    c.to_u8().unwrap_or_default()
+                })
+                .collect();
+            writeln!(code, "    b\"{}\",", row.escape_ascii())?;
+        }
+        writeln!(code, "];")?;
+        writeln!(
+            code,
+            "/// The cells of the source code that the compiled code relies on, as `#`"
+        )?;
+        writeln!(code, "const COMPILED: [&[u8]; HEIGHT] = [")?;
+        for y in 0..height {
+            let row: String = (0..width)
+                .map(|x| {
+                    if compiler.compiled.contains(&position(x, y)) {
+                        '#'
+                    } else {
+                        ' '
+                    }
+                })
+                .collect();
+            writeln!(code, "    b\"{}\",", row)?;
+        }
+        writeln!(code, "];\n")?;
+
+        code.push_str(RUNTIME);
+
+        writeln!(code)?;
+        writeln!(code, "fn main() {{")?;
+        writeln!(code, "    let mut machine = Machine::new();")?;
+        writeln!(code, "    std::process::exit(run(&mut machine));")?;
+        writeln!(code, "}}\n")?;
+        writeln!(
+            code,
+            "/// The compiled program: every block evaluates to the number of the block that follows it."
+        )?;
+        writeln!(code, "fn run(m: &mut Machine) -> i32 {{")?;
+        writeln!(code, "    let mut block = 0;")?;
+        writeln!(code, "    loop {{")?;
+        writeln!(code, "        block = match block {{")?;
+        for (state, body) in blocks {
+            let (x, y, _) = &state.position;
+            writeln!(
+                code,
+                "            // moving {} from ({}, {}){}",
+                direction(&state.delta),
+                x,
+                y,
+                if state.string_mode {
+                    ", in string mode"
+                } else {
+                    ""
+                }
+            )?;
+            writeln!(code, "            {} => {{", compiler.blocks[&state])?;
+            for line in body.lines() {
+                writeln!(code, "                {}", line)?;
+            }
+            writeln!(code, "            }}")?;
+        }
+        writeln!(code, "            _ => unreachable!(),")?;
+        writeln!(code, "        }};")?;
+        writeln!(code, "    }}")?;
+        writeln!(code, "}}")?;
+        Ok(code)
+    }
+}
+
+/// Returns the position `(x, y)` of a cell of the source code.
+fn position<T: FungeInteger>(x: usize, y: usize) -> (T, T, T) {
+    (
+        T::from(x).unwrap_or_default(),
+        T::from(y).unwrap_or_default(),
+        T::zero(),
+    )
+}
+
+/// Returns `delta`, reflected.
+fn reflected<T: FungeInteger>((dx, dy, dz): (T, T, T)) -> (T, T, T) {
+    (-dx, -dy, -dz)
+}
+
+/// Returns the name of the direction of `delta`, for the comments of the compiled code.
+fn direction<T: FungeInteger>((dx, dy, _): &(T, T, T)) -> &'static str {
+    match (dx.signum().to_i8(), dy.signum().to_i8()) {
+        (Some(1), _) => "east",
+        (Some(-1), _) => "west",
+        (_, Some(1)) => "south",
+        _ => "north",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compiled_cells() -> Result<()> {
+        let program = Program::<i32>::from_source(b"1#2.@\n ;x; ", Dialect::Befunge);
+        let mut compiler = Compiler::new(&program);
+        compiler.compile_blocks()?;
+        let mut cells: Vec<_> = compiler.compiled.into_iter().collect();
+        cells.sort();
+        // the cell that `#` skips does not matter, nor does the row the IP never reaches
+        assert_eq!(cells, vec![(0, 0, 0), (1, 0, 0), (3, 0, 0), (4, 0, 0)]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_blocks() -> Result<()> {
+        let program = Program::<i32>::from_source(b"0_@", Dialect::Befunge);
+        let mut compiler = Compiler::new(&program);
+        let blocks = compiler.compile_blocks()?;
+        // the start, the `@` east of `_` and the `0` west of it, which wraps around to the `@`
+        assert_eq!(blocks.len(), 3);
+        assert_eq!(
+            blocks[0].1,
+            "m.push(0);\nif m.pop() == 0 { 1 } else { 2 }\n"
+        );
+        assert_eq!(blocks[1].1, "return m.exit(0);\n");
+        assert_eq!(blocks[2].1, "m.push(0);\nreturn m.exit(0);\n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_unreachable_instructions_are_not_compiled() {
+        for (source, compiles) in [(&b"0#kk@"[..], false), (&b"0#k@"[..], true)] {
+            let program = Program::<i32>::from_source(source, Dialect::Befunge);
+            assert_eq!(Compiler::new(&program).compile_blocks().is_ok(), compiles);
+        }
+    }
+}
@@ -0,0 +1,541 @@
+// The runtime of a program compiled by `bff compile`, which embeds it verbatim (so this file
+// is not a module of bff): Funge-Space, the stack and the streams of the program, along with
+// an interpreter, which takes over from the compiled code as soon as the program writes to
+// a cell that the code was compiled from or, in Funge-98, changes the bounds of Funge-Space.
+
+use std::collections::HashMap;
+use std::io::{self, BufReader, BufWriter, Read, Stdin, Stdout, Write};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const SPACE: Cell = 32;
+
+struct Machine {
+    /// The cells of the source code, row by row
+    cells: Vec<Cell>,
+    /// The non-space cells outside of the source code (Funge-98 only)
+    outside: HashMap<(Cell, Cell), Cell>,
+    /// The number of non-space cells on every column and row that holds any
+    columns: HashMap<Cell, usize>,
+    rows: HashMap<Cell, usize>,
+    /// The least (inclusive) and the greatest (exclusive) point of Funge-Space
+    lower: (Cell, Cell),
+    upper: (Cell, Cell),
+    /// The cells of the source code that the compiled code was compiled from
+    compiled: Vec<bool>,
+    stack: Vec<Cell>,
+    position: (Cell, Cell),
+    delta: (Cell, Cell),
+    string_mode: bool,
+    stdin: BufReader<Stdin>,
+    stdout: BufWriter<Stdout>,
+    /// The state of the generator of `?`
+    seed: u64,
+}
+
+impl Machine {
+    fn new() -> Self {
+        let mut machine = Self {
+            cells: Vec::with_capacity(WIDTH * HEIGHT),
+            outside: HashMap::new(),
+            columns: HashMap::new(),
+            rows: HashMap::new(),
+            lower: (0, 0),
+            upper: (WIDTH as Cell, HEIGHT as Cell),
+            compiled: Vec::with_capacity(WIDTH * HEIGHT),
+            stack: Vec::new(),
+            position: (0, 0),
+            delta: (1, 0),
+            string_mode: false,
+            stdin: BufReader::new(io::stdin()),
+            stdout: BufWriter::new(io::stdout()),
+            seed: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|time| time.as_nanos() as u64)
+                .unwrap_or_default()
+                | 1,
+        };
+        for (y, (row, compiled)) in SOURCE.iter().zip(COMPILED.iter()).enumerate() {
+            for (x, (c, mark)) in row.iter().zip(compiled.iter()).enumerate() {
+                machine.cells.push(*c as Cell);
+                machine.compiled.push(*mark == b'#');
+                if *c as Cell != SPACE {
+                    *machine.columns.entry(x as Cell).or_default() += 1;
+                    *machine.rows.entry(y as Cell).or_default() += 1;
+                }
+            }
+        }
+        machine
+    }
+
+    fn push(&mut self, x: Cell) {
+        self.stack.push(x);
+    }
+
+    fn pop(&mut self) -> Cell {
+        self.stack.pop().unwrap_or(0)
+    }
+
+    /// Writes a warning to stderr, after everything written to stdout so far.
+    fn warn(&mut self, message: &str) {
+        let _ = self.stdout.flush();
+        eprintln!("warning: {}", message);
+    }
+
+    /// Ends the program with a runtime error, like bff does.
+    fn fail(&mut self, message: &str) -> ! {
+        let _ = self.stdout.flush();
+        eprintln!("Error: Runtime error\n\nCaused by:\n    {}", message);
+        std::process::exit(1)
+    }
+
+    /// Ends the program with exit code `code`.
+    fn exit(&mut self, code: i32) -> i32 {
+        let _ = self.stdout.flush();
+        code
+    }
+
+    /// Ends the program with the exit code that `q` pops.
+    fn quit(&mut self) -> i32 {
+        let code = i32::try_from(self.pop()).unwrap_or_default();
+        self.exit(code)
+    }
+
+    fn inside(&self, (x, y): (Cell, Cell)) -> bool {
+        x >= self.lower.0 && x < self.upper.0 && y >= self.lower.1 && y < self.upper.1
+    }
+
+    /// Returns the index of the cell on `(x, y)` in the cells of the source code, if it lies there.
+    fn index(&self, (x, y): (Cell, Cell)) -> Option<usize> {
+        if x >= 0 && (x as usize) < WIDTH && y >= 0 && (y as usize) < HEIGHT {
+            Some(y as usize * WIDTH + x as usize)
+        } else {
+            None
+        }
+    }
+
+    fn get(&self, position: (Cell, Cell)) -> Cell {
+        if !self.inside(position) {
+            return SPACE;
+        }
+        match self.index(position) {
+            Some(i) => self.cells[i],
+            None => self.outside.get(&position).copied().unwrap_or(SPACE),
+        }
+    }
+
+    /**
+    Puts `c` on `position`, growing (or shrinking) Funge-Space like bff does, and returns
+    whether the compiled code no longer matches Funge-Space, so the interpreter has to take over.
+    */
+    fn put(&mut self, position: (Cell, Cell), c: Cell) -> bool {
+        if BEFUNGE_93 && !self.inside(position) {
+            self.warn("A cell was put outside of the fixed Funge-Space and was dropped.");
+            return false;
+        }
+        let bounds = (self.lower, self.upper);
+        let previous = self.get(position);
+        if previous == SPACE && c != SPACE {
+            *self.columns.entry(position.0).or_default() += 1;
+            *self.rows.entry(position.1).or_default() += 1;
+        } else if previous != SPACE && c == SPACE {
+            for (counts, key) in [
+                (&mut self.columns, position.0),
+                (&mut self.rows, position.1),
+            ] {
+                if let Some(count) = counts.get_mut(&key) {
+                    *count -= 1;
+                    if *count == 0 {
+                        counts.remove(&key);
+                    }
+                }
+            }
+        }
+        let compiled = match self.index(position) {
+            Some(i) => {
+                self.cells[i] = c;
+                self.compiled[i] && previous != c
+            }
+            None => {
+                if c == SPACE {
+                    self.outside.remove(&position);
+                } else {
+                    self.outside.insert(position, c);
+                }
+                false
+            }
+        };
+        if !BEFUNGE_93 {
+            self.lower = (self.lower.0.min(position.0), self.lower.1.min(position.1));
+            self.upper = (
+                self.upper.0.max(position.0 + 1),
+                self.upper.1.max(position.1 + 1),
+            );
+            if c == SPACE {
+                self.shrink(position);
+            }
+        }
+        compiled || bounds != (self.lower, self.upper)
+    }
+
+    /// Drops the empty rows and columns on the edges of Funge-Space that `(x, y)` lies on.
+    fn shrink(&mut self, (x, y): (Cell, Cell)) {
+        let empty = |counts: &HashMap<Cell, usize>, key: Cell| !counts.contains_key(&key);
+        if y == self.upper.1 - 1 {
+            while self.upper.1 - self.lower.1 > 1 && empty(&self.rows, self.upper.1 - 1) {
+                self.upper.1 -= 1;
+            }
+        } else if y == self.lower.1 {
+            while self.upper.1 - self.lower.1 > 1 && empty(&self.rows, self.lower.1) {
+                self.lower.1 += 1;
+            }
+        }
+        if x == self.upper.0 - 1 {
+            while self.upper.0 - self.lower.0 > 1 && empty(&self.columns, self.upper.0 - 1) {
+                self.upper.0 -= 1;
+            }
+        } else if x == self.lower.0 {
+            while self.upper.0 - self.lower.0 > 1 && empty(&self.columns, self.lower.0) {
+                self.lower.0 += 1;
+            }
+        }
+    }
+
+    /// Returns the position one step away from `(x, y)`, wrapping around the bounds.
+    fn step(&self, (x, y): (Cell, Cell), (dx, dy): (Cell, Cell)) -> (Cell, Cell) {
+        let next = (x + dx, y + dy);
+        if self.inside(next) || !self.inside((x, y)) {
+            return next;
+        }
+        match (dx, dy) {
+            (1, _) => (self.lower.0, y),
+            (-1, _) => (self.upper.0 - 1, y),
+            (_, 1) => (x, self.lower.1),
+            _ => (x, self.upper.1 - 1),
+        }
+    }
+
+    fn advance(&mut self) {
+        self.position = self.step(self.position, self.delta);
+    }
+
+    fn read_byte(&mut self) -> Option<u8> {
+        let _ = self.stdout.flush();
+        let mut byte = [0];
+        loop {
+            match self.stdin.read(&mut byte) {
+                Ok(0) => return None,
+                Ok(_) => return Some(byte[0]),
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(_) => self.fail("Failed while reading raw input from stdin"),
+            }
+        }
+    }
+
+    /// Reads a line from stdin and returns the decimal number in it, or `None` at the end of input.
+    fn read_integer(&mut self) -> Option<Cell> {
+        let mut line = Vec::new();
+        while let Some(b) = self.read_byte() {
+            line.push(b);
+            if b == b'\n' {
+                break;
+            }
+        }
+        if line.is_empty() {
+            return None;
+        }
+        let mut res: Cell = 0;
+        let mut discard_done = false;
+        let mut negative = false;
+        for dchar in String::from_utf8_lossy(&line).trim().chars() {
+            if let Some(d) = dchar.to_digit(10) {
+                discard_done = true;
+                let (shifted_res, mul_overflowed) = res.overflowing_mul(10);
+                let (new_res, add_overflowed) = shifted_res.overflowing_add(d as Cell);
+                if mul_overflowed || add_overflowed {
+                    break;
+                }
+                res = new_res;
+            } else if discard_done {
+                break;
+            } else if dchar == '-' {
+                negative = true;
+                discard_done = true;
+            }
+        }
+        if negative {
+            let (neg_res, underflowed) = res.overflowing_mul(-1);
+            res = if underflowed { neg_res / 10 } else { neg_res };
+        }
+        Some(res)
+    }
+
+    /// Asks the user for the result of `operation` (a division or remainder by 0), like Befunge-93 does.
+    fn ask_for_result(&mut self, operation: &str) -> Cell {
+        let _ = write!(self.stdout, "What do you want {} to be? ", operation);
+        self.read_integer().unwrap_or_default()
+    }
+
+    fn add(&mut self) {
+        let (a, b) = (self.pop(), self.pop());
+        let (res, overflowed) = a.overflowing_add(b);
+        if overflowed {
+            self.warn("An addition resulted in overflow.");
+        }
+        self.push(res);
+    }
+
+    fn subtract(&mut self) {
+        let (a, b) = (self.pop(), self.pop());
+        let (res, overflowed) = b.overflowing_sub(a);
+        if overflowed {
+            self.warn("A subtraction resulted in overflow.");
+        }
+        self.push(res);
+    }
+
+    fn multiply(&mut self) {
+        let (a, b) = (self.pop(), self.pop());
+        let (res, overflowed) = a.overflowing_mul(b);
+        if overflowed {
+            self.warn("A multiplication resulted in overflow.");
+        }
+        self.push(res);
+    }
+
+    fn divide(&mut self) {
+        let (a, b) = (self.pop(), self.pop());
+        if a == 0 && BEFUNGE_93 {
+            let c = self.ask_for_result(&format!("{}/0", b));
+            self.push(c);
+        } else if a == 0 {
+            self.warn("Division by 0 occured. Will return 0 as per the language specification.");
+            self.push(0);
+        } else {
+            let (res, overflowed) = b.overflowing_div(a);
+            if overflowed {
+                self.warn("A division resulted in overflow.");
+            }
+            self.push(res);
+        }
+    }
+
+    fn remainder(&mut self) {
+        let (a, b) = (self.pop(), self.pop());
+        if a == 0 && BEFUNGE_93 {
+            let c = self.ask_for_result(&format!("{}%0", b));
+            self.push(c);
+        } else if a == 0 {
+            self.warn("Remainder with divisor of 0 occured. Will return 0 as per the language specification.");
+            self.push(0);
+        } else {
+            let (res, overflowed) = b.overflowing_rem(a);
+            if overflowed {
+                self.warn("A remainder resulted in overflow.");
+            }
+            self.push(res);
+        }
+    }
+
+    fn not(&mut self) {
+        let a = self.pop();
+        self.push((a == 0) as Cell);
+    }
+
+    fn greater(&mut self) {
+        let (a, b) = (self.pop(), self.pop());
+        self.push((b > a) as Cell);
+    }
+
+    fn duplicate(&mut self) {
+        let a = self.pop();
+        self.push(a);
+        self.push(a);
+    }
+
+    fn swap(&mut self) {
+        let (a, b) = (self.pop(), self.pop());
+        self.push(a);
+        self.push(b);
+    }
+
+    fn discard(&mut self) {
+        self.pop();
+    }
+
+    fn clear(&mut self) {
+        self.stack.clear();
+    }
+
+    fn print_integer(&mut self) {
+        let a = self.pop();
+        if write!(self.stdout, "{} ", a).is_err() {
+            self.fail("Failed to write an integer to stdout");
+        }
+    }
+
+    fn print_char(&mut self) {
+        let c = u32::try_from(self.pop()).unwrap_or_default();
+        let c = char::from_u32(c).unwrap_or_default();
+        if write!(self.stdout, "{}", c).is_err() {
+            self.fail("Failed to write a character to stdout");
+        }
+    }
+
+    fn get_cell(&mut self) {
+        let (y, x) = (self.pop(), self.pop());
+        let c = self.get((x, y));
+        self.push(c);
+    }
+
+    /// Executes `p`; see `put`.
+    fn put_cell(&mut self) -> bool {
+        let (y, x) = (self.pop(), self.pop());
+        let c = self.pop();
+        self.put((x, y), c)
+    }
+
+    /// Executes `s`, which stores a cell on `position`; see `put`.
+    fn store(&mut self, position: (Cell, Cell)) -> bool {
+        let c = self.pop();
+        self.put(position, c)
+    }
+
+    fn read_integer_input(&mut self) {
+        let n = match self.read_integer() {
+            Some(n) => n,
+            // Befunge-93 reads -1 at the end of input
+            None if BEFUNGE_93 => -1,
+            None => 0,
+        };
+        self.push(n);
+    }
+
+    fn read_char_input(&mut self) {
+        match self.read_byte() {
+            Some(c) => self.push(c as Cell),
+            // Befunge-93 reads -1 at the end of input
+            None if BEFUNGE_93 => self.push(-1),
+            None => self.fail("Failed to read character from stdin"),
+        }
+    }
+
+    /// Returns a random number in `0..n`, for `?`.
+    fn random(&mut self, n: u64) -> u64 {
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 7;
+        self.seed ^= self.seed << 17;
+        self.seed % n
+    }
+
+    /**
+    Hands the program over to the interpreter, right after the compiled code executed
+    the instruction on `position`, with `delta`, which moves the IP `moves` cells away.
+    */
+    fn resume(&mut self, position: (Cell, Cell), delta: (Cell, Cell), moves: usize) -> i32 {
+        self.position = position;
+        self.delta = delta;
+        for _ in 0..moves {
+            self.advance();
+        }
+        self.interpret()
+    }
+
+    /// Interprets the program from the current position of the IP, until it terminates.
+    fn interpret(&mut self) -> i32 {
+        loop {
+            let x = self.get(self.position);
+            let c = char::from_u32(u32::try_from(x).unwrap_or_default()).unwrap_or_default();
+            if self.string_mode {
+                match c {
+                    '"' => self.string_mode = false,
+                    // in Funge-98, consecutive spaces are pushed as a single one
+                    ' ' if !BEFUNGE_93 => {
+                        self.push(x);
+                        while self.get(self.step(self.position, self.delta)) == SPACE {
+                            self.advance();
+                        }
+                    }
+                    _ => self.push(x),
+                }
+                self.advance();
+                continue;
+            }
+            match c {
+                c if BEFUNGE_93 && !BEFUNGE_93_INSTRUCTIONS.contains(c) => self.reflect(),
+                '0'..='9' | 'a'..='f' => self.push(c.to_digit(16).unwrap_or_default() as Cell),
+                '+' => self.add(),
+                '-' => self.subtract(),
+                '*' => self.multiply(),
+                '/' => self.divide(),
+                '%' => self.remainder(),
+                '!' => self.not(),
+                '`' => self.greater(),
+                '>' => self.delta = (1, 0),
+                '<' => self.delta = (-1, 0),
+                '^' => self.delta = (0, -1),
+                'v' => self.delta = (0, 1),
+                '?' => self.delta = [(1, 0), (-1, 0), (0, 1), (0, -1)][self.random(4) as usize],
+                '_' => self.delta = if self.pop() == 0 { (1, 0) } else { (-1, 0) },
+                '|' => self.delta = if self.pop() == 0 { (0, 1) } else { (0, -1) },
+                '"' => self.string_mode = true,
+                ':' => self.duplicate(),
+                '\\' => self.swap(),
+                '$' => self.discard(),
+                '.' => self.print_integer(),
+                ',' => self.print_char(),
+                '#' => self.advance(),
+                'p' => {
+                    self.put_cell();
+                }
+                'g' => self.get_cell(),
+                '&' => self.read_integer_input(),
+                '~' => self.read_char_input(),
+                '@' => return self.exit(0),
+                ' ' | 'z' => {}
+                '[' => self.delta = (self.delta.1, -self.delta.0),
+                ']' => self.delta = (-self.delta.1, self.delta.0),
+                ';' => loop {
+                    self.advance();
+                    if self.get(self.position) == ';' as Cell {
+                        break;
+                    }
+                },
+                'n' => self.clear(),
+                'w' => {
+                    let (b, a) = (self.pop(), self.pop());
+                    if a < b {
+                        self.delta = (self.delta.1, -self.delta.0);
+                    } else if a > b {
+                        self.delta = (-self.delta.1, self.delta.0);
+                    }
+                }
+                '\'' => {
+                    let c = self.get((
+                        self.position.0 + self.delta.0,
+                        self.position.1 + self.delta.1,
+                    ));
+                    self.push(c);
+                    self.advance();
+                }
+                's' => {
+                    self.store((
+                        self.position.0 + self.delta.0,
+                        self.position.1 + self.delta.1,
+                    ));
+                    self.advance();
+                }
+                'q' => return self.quit(),
+                c if UNSUPPORTED_INSTRUCTIONS.contains(c) => self.fail(&format!(
+                    "The `{}` instruction is not supported by compiled programs",
+                    c
+                )),
+                _ => self.reflect(),
+            }
+            self.advance();
+        }
+    }
+
+    fn reflect(&mut self) {
+        self.delta = (-self.delta.0, -self.delta.1);
+    }
+}
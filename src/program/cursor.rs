@@ -2,44 +2,55 @@ use std::default::Default;
 
 use super::{bounds::Bounds, delta::Delta, fungetypes::FungeInteger};
 
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub(super) struct Cursor<T: FungeInteger> {
     x: T,
     y: T,
+    z: T,
     delta: Delta<T>,
-    storage_offset: (T, T),
+    storage_offset: (T, T, T),
 }
 
 impl<T: FungeInteger> Cursor<T> {
-    pub fn position(&self) -> (T, T) {
-        (self.x, self.y)
+    pub fn position(&self) -> (T, T, T) {
+        (self.x.clone(), self.y.clone(), self.z.clone())
     }
 
     pub fn delta(&self) -> &Delta<T> {
         &self.delta
     }
 
-    pub fn storage_offset(&self) -> (T, T) {
-        self.storage_offset
+    pub fn delta_members(&self) -> (T, T, T) {
+        (
+            self.delta.x.clone(),
+            self.delta.y.clone(),
+            self.delta.z.clone(),
+        )
+    }
+
+    pub fn storage_offset(&self) -> (T, T, T) {
+        self.storage_offset.clone()
     }
 
-    pub fn set_position(&mut self, x: T, y: T) {
+    pub fn set_position(&mut self, x: T, y: T, z: T) {
         self.x = x;
         self.y = y;
+        self.z = z;
     }
 
     pub fn set_delta(&mut self, new_delta: Delta<T>) {
         self.delta = new_delta;
     }
 
-    pub fn set_delta_members(&mut self, new_delta: (T, T)) {
+    pub fn set_delta_members(&mut self, new_delta: (T, T, T)) {
         self.delta = Delta {
             x: new_delta.0,
             y: new_delta.1,
+            z: new_delta.2,
         };
     }
 
-    pub fn set_storage_offset(&mut self, so: (T, T)) {
+    pub fn set_storage_offset(&mut self, so: (T, T, T)) {
         self.storage_offset = so;
     }
 
@@ -48,27 +59,26 @@ impl<T: FungeInteger> Cursor<T> {
         self.delta.reflect();
     }
 
-    /// Rotates delta 90 degrees to the left (counterclockwise).
+    /// Rotates delta 90 degrees to the left (counterclockwise), around the z axis.
     pub fn turn_left(&mut self) {
-        let x = self.delta.x;
-        let y = self.delta.y;
-        self.delta.x = y;
+        let x = self.delta.x.clone();
+        self.delta.x = self.delta.y.clone();
         self.delta.y = -x;
     }
 
-    /// Rotates delta 90 degrees to the right (clockwise).
+    /// Rotates delta 90 degrees to the right (clockwise), around the z axis.
     pub fn turn_right(&mut self) {
-        let x = self.delta.x;
-        let y = self.delta.y;
-        self.delta.x = -y;
+        let x = self.delta.x.clone();
+        self.delta.x = -self.delta.y.clone();
         self.delta.y = x;
     }
 
     /// Returns the `position` relative to this cursor's `storage offset`.
-    pub fn translate_to_storage_position(&self, position: (T, T)) -> (T, T) {
+    pub fn translate_to_storage_position(&self, position: (T, T, T)) -> (T, T, T) {
         (
-            position.0 + self.storage_offset.0,
-            position.1 + self.storage_offset.1,
+            position.0 + self.storage_offset.0.clone(),
+            position.1 + self.storage_offset.1.clone(),
+            position.2 + self.storage_offset.2.clone(),
         )
     }
 
@@ -78,23 +88,7 @@ impl<T: FungeInteger> Cursor<T> {
     the cursor's `position`.
     */
     pub fn r#move(&mut self, bounds: &Bounds<T>) {
-        let (x, y) = self.position();
-        let delta = self.delta();
-        let mut new_x = x + delta.x;
-        let mut new_y = y + delta.y;
-        if bounds.out_of_bounds((new_x, new_y)) {
-            self.reflect();
-            loop {
-                new_x += self.delta.x;
-                new_y += self.delta.y;
-                if bounds.out_of_bounds((new_x, new_y)) {
-                    break;
-                }
-            }
-            self.reflect();
-            new_x += self.delta.x;
-            new_y += self.delta.y;
-        }
-        self.set_position(new_x, new_y);
+        let (x, y, z) = bounds.step(self.position(), &self.delta_members());
+        self.set_position(x, y, z);
     }
 }
@@ -1,29 +1,33 @@
-use rand::{
-    distributions::{Distribution, Standard},
-    Rng,
-};
+use rand::Rng;
 use std::default::Default;
 use std::ops;
 
 use super::fungetypes::FungeInteger;
 
+#[derive(Clone)]
 pub(super) struct Delta<T: FungeInteger> {
     pub x: T,
     pub y: T,
+    pub z: T,
 }
 
 impl<T: FungeInteger> ops::MulAssign<T> for Delta<T> {
     fn mul_assign(&mut self, factor: T) {
-        self.x *= factor;
-        self.y *= factor;
+        self.x *= factor.clone();
+        self.y *= factor.clone();
+        self.z *= factor;
     }
 }
 
-impl<T: FungeInteger> ops::Add<(T, T)> for &Delta<T> {
-    type Output = (T, T);
+impl<T: FungeInteger> ops::Add<(T, T, T)> for &Delta<T> {
+    type Output = (T, T, T);
 
-    fn add(self, rhs: (T, T)) -> Self::Output {
-        (self.x + rhs.0, self.y + rhs.1)
+    fn add(self, rhs: (T, T, T)) -> Self::Output {
+        (
+            self.x.clone() + rhs.0,
+            self.y.clone() + rhs.1,
+            self.z.clone() + rhs.2,
+        )
     }
 }
 
@@ -32,6 +36,7 @@ impl<T: FungeInteger> Delta<T> {
         Self {
             x: T::one(),
             y: T::zero(),
+            z: T::zero(),
         }
     }
 
@@ -39,6 +44,7 @@ impl<T: FungeInteger> Delta<T> {
         Self {
             x: T::zero(),
             y: T::one(),
+            z: T::zero(),
         }
     }
 
@@ -46,6 +52,7 @@ impl<T: FungeInteger> Delta<T> {
         Self {
             x: -T::one(),
             y: T::zero(),
+            z: T::zero(),
         }
     }
 
@@ -53,6 +60,35 @@ impl<T: FungeInteger> Delta<T> {
         Self {
             x: T::zero(),
             y: -T::one(),
+            z: T::zero(),
+        }
+    }
+
+    pub fn high() -> Self {
+        Self {
+            x: T::zero(),
+            y: T::zero(),
+            z: T::one(),
+        }
+    }
+
+    pub fn low() -> Self {
+        Self {
+            x: T::zero(),
+            y: T::zero(),
+            z: -T::one(),
+        }
+    }
+
+    /// Returns one of the cardinal directions of a Funge-Space of `dimensions` dimensions, at random.
+    pub fn random(dimensions: usize) -> Self {
+        match rand::thread_rng().gen_range(0..2 * dimensions) {
+            0 => Self::east(),
+            1 => Self::west(),
+            2 => Self::south(),
+            3 => Self::north(),
+            4 => Self::high(),
+            _ => Self::low(),
         }
     }
 
@@ -66,14 +102,3 @@ impl<T: FungeInteger> Default for Delta<T> {
         Self::east()
     }
 }
-
-impl<T: FungeInteger> Distribution<Delta<T>> for Standard {
-    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Delta<T> {
-        match rng.gen_range(0..=3) {
-            0 => Delta::<T>::east(),
-            1 => Delta::<T>::south(),
-            2 => Delta::<T>::west(),
-            _ => Delta::<T>::north(),
-        }
-    }
-}
@@ -0,0 +1,50 @@
+use std::path::Path;
+
+/**
+The Funge dialect a program is written in i.e., the number of dimensions
+of its Funge-Space or, for Befunge-93, the language standard altogether.
+
+Vectors always have three components internally; the components beyond the
+dimensions of the dialect are always 0 and are never pushed onto (or popped from) a stack.
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Dialect {
+    /// One dimensional Funge-Space; the whole source code is a single line
+    Unefunge,
+    /// Two dimensional Funge-Space
+    #[default]
+    Befunge,
+    /// Two dimensional, fixed 80x25 Funge-Space and the Befunge-93 instruction set
+    Befunge93,
+    /// Three dimensional Funge-Space; a form feed in the source code starts a new plane
+    Trefunge,
+}
+
+impl Dialect {
+    /// Returns the number of dimensions of this dialect, as reported by `y` (item 7).
+    pub fn dimensions(&self) -> usize {
+        match self {
+            Dialect::Unefunge => 1,
+            Dialect::Befunge | Dialect::Befunge93 => 2,
+            Dialect::Trefunge => 3,
+        }
+    }
+
+    /// Returns the fixed width and height of Funge-Space, if this dialect has any.
+    pub fn fixed_size(&self) -> Option<(usize, usize)> {
+        match self {
+            Dialect::Befunge93 => Some((80, 25)),
+            _ => None,
+        }
+    }
+
+    /// Returns the dialect that the extension of the source file `path` suggests.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("uf") => Dialect::Unefunge,
+            Some("bf") => Dialect::Befunge93,
+            Some("tf") => Dialect::Trefunge,
+            _ => Dialect::Befunge,
+        }
+    }
+}
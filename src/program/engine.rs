@@ -0,0 +1,19 @@
+/**
+The way a program is executed.
+
+The interpreter decodes and executes every instruction as the IP reaches it.
+The fast engine caches the straight-line runs of simple instructions (like digits,
+arithmetic and stack manipulation) that lie between direction changes, and executes
+each run at once; a run is dropped as soon as one of its cells is written to.
+Both behave the exact same way, tick by tick; the fast engine executes the runs one
+instruction at a time, like the interpreter, while the program has more than one IP
+or while it is traced, profiled or stepped through.
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Engine {
+    /// Decode and execute every instruction as it is reached
+    #[default]
+    Interpreter,
+    /// Cache and execute the runs of simple instructions at once
+    Fast,
+}
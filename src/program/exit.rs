@@ -0,0 +1,18 @@
+/// The way a program terminated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Exit {
+    /// The last IP executed `@`
+    Ended,
+    /// An IP executed `q`, with the given exit code
+    Quit(i32),
+}
+
+impl Exit {
+    /// Returns the exit code of the program: 0, unless `q` says otherwise.
+    pub fn code(&self) -> i32 {
+        match self {
+            Exit::Ended => 0,
+            Exit::Quit(code) => *code,
+        }
+    }
+}
@@ -0,0 +1,166 @@
+use std::{fs, io};
+
+use super::{fungetypes::FungeInteger, split_source, Program};
+
+impl<T: FungeInteger> Program<T> {
+    /**
+    Loads the file `filename` into Funge-Space, with its least point at `offset`,
+    and returns the size of the (bounding) box of the loaded cells.
+
+    In text mode, the file is split in lines (and, in Trefunge, in planes) exactly
+    like a source file of the program's dialect is; spaces are transparent i.e.,
+    they do not overwrite the cells they fall upon.
+
+    In binary mode, the file is loaded as a single line and every byte
+    (including EOL characters and spaces) is stored in Funge-Space as is.
+    */
+    pub(super) fn input_file(
+        &mut self,
+        filename: &str,
+        binary: bool,
+        offset: (T, T, T),
+    ) -> io::Result<(T, T, T)> {
+        let contents = fs::read(filename)?;
+        let mut planes = if binary {
+            vec![vec![contents]]
+        } else {
+            split_source(&contents, self.dialect)
+        };
+        // trailing empty lines (and planes) do not contribute to the size of the box
+        for lines in planes.iter_mut() {
+            while lines.last().is_some_and(|line| line.is_empty()) {
+                lines.pop();
+            }
+        }
+        while planes.last().is_some_and(|lines| lines.is_empty()) {
+            planes.pop();
+        }
+
+        for (k, lines) in planes.iter().enumerate() {
+            for (j, line) in lines.iter().enumerate() {
+                for (i, &b) in line.iter().enumerate() {
+                    if binary || b != b' ' {
+                        let position = (
+                            offset.0.clone() + T::from(i).unwrap_or_default(),
+                            offset.1.clone() + T::from(j).unwrap_or_default(),
+                            offset.2.clone() + T::from(k).unwrap_or_default(),
+                        );
+                        self.put_cell(position, T::from(b).unwrap_or_default());
+                    }
+                }
+            }
+        }
+
+        let width = planes
+            .iter()
+            .flatten()
+            .map(|line| line.len())
+            .max()
+            .unwrap_or_default();
+        let height = planes
+            .iter()
+            .map(|lines| lines.len())
+            .max()
+            .unwrap_or_default();
+        Ok((
+            T::from(width).unwrap_or_default(),
+            T::from(height).unwrap_or_default(),
+            T::from(planes.len()).unwrap_or_default(),
+        ))
+    }
+
+    /**
+    Writes the box of Funge-Space with least point `offset` and size `size`
+    to the file `filename`, one line per row and, in Trefunge, one form feed
+    between every two planes.
+
+    In text mode, the spaces at the end of each line and the empty lines
+    at the end of each plane are not written out.
+
+    The box is clamped to the bounds of Funge-Space, outside of which there are
+    only spaces; it fails if the clamped box is still too large to be written out.
+    */
+    pub(super) fn output_file(
+        &self,
+        filename: &str,
+        text: bool,
+        offset: (T, T, T),
+        size: (T, T, T),
+    ) -> io::Result<()> {
+        // the (unused) components of the size beyond the dimensions of the program are 0,
+        // yet a Unefunge program has a single row and a Befunge program has a single plane
+        let dimensions = self.dialect.dimensions();
+        let bounds = self.grid.get_bounds();
+        let (x, width) = clamp(offset.0, size.0, bounds.lower_x(), bounds.upper_x())?;
+        let (y, height) = if dimensions > 1 {
+            clamp(offset.1, size.1, bounds.lower_y(), bounds.upper_y())?
+        } else {
+            (offset.1, 1)
+        };
+        let (z, depth) = if dimensions > 2 {
+            clamp(offset.2, size.2, bounds.lower_z(), bounds.upper_z())?
+        } else {
+            (offset.2, 1)
+        };
+        let offset = (x, y, z);
+
+        let planes: Vec<Vec<u8>> = (0..depth)
+            .map(|k| {
+                let mut lines: Vec<Vec<u8>> = (0..height)
+                    .map(|j| {
+                        (0..width)
+                            .map(|i| {
+                                let position = (
+                                    offset.0.clone() + T::from(i).unwrap_or_default(),
+                                    offset.1.clone() + T::from(j).unwrap_or_default(),
+                                    offset.2.clone() + T::from(k).unwrap_or_default(),
+                                );
+                                self.get_cell(position).to_u8().unwrap_or_default()
+                            })
+                            .collect()
+                    })
+                    .collect();
+
+                if text {
+                    for line in lines.iter_mut() {
+                        while line.last() == Some(&b' ') {
+                            line.pop();
+                        }
+                    }
+                    while lines.last().is_some_and(|line| line.is_empty()) {
+                        lines.pop();
+                    }
+                }
+
+                lines.join(&b'\n')
+            })
+            .collect();
+
+        fs::write(filename, planes.join(&b'\x0c'))
+    }
+}
+
+/**
+Clamps the range of `size` coordinates from `offset` to the (inclusive) range
+from `lower` to `upper`, and returns its first coordinate and its length.
+*/
+fn clamp<T: FungeInteger>(offset: T, size: T, lower: T, upper: T) -> io::Result<(T, usize)> {
+    if size <= T::zero() {
+        return Ok((offset, 0));
+    }
+    // a range that would run past the greatest coordinate of a cell runs up to `upper` instead
+    let last = match offset.overflowing_add(&(size - T::one())) {
+        (last, false) if last < upper => last,
+        _ => upper,
+    };
+    let first = if offset > lower { offset } else { lower };
+    if first > last {
+        return Ok((first, 0));
+    }
+    match last.overflowing_sub(&first) {
+        (extent, false) => extent.to_usize().and_then(|n| n.checked_add(1)),
+        (_, true) => None,
+    }
+    .map(|length| (first, length))
+    .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "box too large"))
+}
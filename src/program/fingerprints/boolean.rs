@@ -0,0 +1,71 @@
+use anyhow::Result;
+
+use super::{Context, Fingerprint, FungeInteger};
+
+/// `BOOL`: bitwise logical operations.
+pub(super) struct Bool;
+
+impl<T: FungeInteger> Fingerprint<T> for Bool {
+    fn name(&self) -> &'static str {
+        "BOOL"
+    }
+
+    fn instructions(&self) -> &'static str {
+        "ANOX"
+    }
+
+    fn execute(&self, instruction: char, funge: &mut dyn Context<T>) -> Result<()> {
+        match instruction {
+            // NOT: pop a, then push the bitwise NOT of a
+            'N' => {
+                let a = funge.pop();
+                funge.push(!a);
+            }
+            // AND, OR, XOR: pop b and a, then push a op b
+            _ => {
+                let (b, a) = (funge.pop(), funge.pop());
+                funge.push(match instruction {
+                    'A' => a & b,
+                    'O' => a | b,
+                    'X' => a ^ b,
+                    _ => unreachable!("BOOL does not provide {}", instruction),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::program::fingerprints::execute_on;
+    use anyhow::ensure;
+
+    #[test]
+    fn test_binary() -> Result<()> {
+        for (instruction, expected) in [('A', 0b1000), ('O', 0b1110), ('X', 0b0110)] {
+            ensure!(execute_on(&Bool, instruction, &[0b1100, 0b1010])? == (vec![expected], false));
+        }
+        ensure!(execute_on(&Bool, 'A', &[i32::MIN, -1])? == (vec![i32::MIN], false));
+        ensure!(execute_on(&Bool, 'O', &[i32::MIN, i32::MAX])? == (vec![-1], false));
+        ensure!(execute_on(&Bool, 'X', &[i32::MIN, -1])? == (vec![i32::MAX], false));
+        Ok(())
+    }
+
+    #[test]
+    fn test_not() -> Result<()> {
+        ensure!(execute_on(&Bool, 'N', &[0])? == (vec![-1], false));
+        ensure!(execute_on(&Bool, 'N', &[5])? == (vec![-6], false));
+        ensure!(execute_on(&Bool, 'N', &[i32::MIN])? == (vec![i32::MAX], false));
+        ensure!(execute_on(&Bool, 'N', &[i32::MAX])? == (vec![i32::MIN], false));
+        Ok(())
+    }
+
+    #[test]
+    fn test_empty_stack() -> Result<()> {
+        ensure!(execute_on(&Bool, 'N', &[])? == (vec![-1], false));
+        ensure!(execute_on(&Bool, 'A', &[7])? == (vec![0], false));
+        Ok(())
+    }
+}
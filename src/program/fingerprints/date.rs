@@ -0,0 +1,146 @@
+use anyhow::Result;
+use chrono::{Datelike, Duration, NaiveDate};
+
+use super::{Context, Fingerprint, FungeInteger};
+
+/**
+`DATE`: calendar date functions, on the proleptic Gregorian calendar.
+
+Dates are pushed and popped as three cells (year, month and day), with the day on top;
+an invalid date reflects.
+*/
+pub(super) struct Date;
+
+/// The Julian day of (the noon of) 0000-12-31, the day before the first day of the Common Era.
+const JULIAN_DAY_OFFSET: i64 = 1721425;
+
+impl<T: FungeInteger> Fingerprint<T> for Date {
+    fn name(&self) -> &'static str {
+        "DATE"
+    }
+
+    fn instructions(&self) -> &'static str {
+        "ACDJTWY"
+    }
+
+    fn execute(&self, instruction: char, funge: &mut dyn Context<T>) -> Result<()> {
+        let result = match instruction {
+            // Add (a possibly negative number of) days to a date; more days than fit in an `i32`
+            // would take any date out of the range of dates anyway
+            'A' => {
+                let days = funge.pop().to_i32();
+                pop_date(funge)
+                    .zip(days)
+                    .and_then(|(date, days)| date.checked_add_signed(Duration::days(days.into())))
+                    .map(|date| push_date(funge, date))
+            }
+            // Convert a Julian day to a date
+            'C' => {
+                let julian_day = funge.pop().to_i64();
+                julian_day
+                    .and_then(|jd| jd.checked_sub(JULIAN_DAY_OFFSET))
+                    .and_then(|days| i32::try_from(days).ok())
+                    .and_then(NaiveDate::from_num_days_from_ce_opt)
+                    .map(|date| push_date(funge, date))
+            }
+            // Days between two dates: pop date2 and date1, then push date1 - date2
+            'D' => {
+                let (date2, date1) = (pop_date(funge), pop_date(funge));
+                date1
+                    .zip(date2)
+                    .map(|(date1, date2)| push_number(funge, (date1 - date2).num_days()))
+            }
+            // Convert a date to a Julian day
+            'J' => pop_date(funge).map(|date| {
+                let days: i64 = date.num_days_from_ce().into();
+                push_number(funge, days + JULIAN_DAY_OFFSET)
+            }),
+            // Convert a year and a (0-based) day of the year to a date
+            'T' => {
+                let (day, year) = (funge.pop().to_u32(), funge.pop().to_i32());
+                year.zip(day)
+                    .and_then(|(year, day)| NaiveDate::from_yo_opt(year, day.checked_add(1)?))
+                    .map(|date| push_date(funge, date))
+            }
+            // Day of the week (0 is Monday)
+            'W' => pop_date(funge)
+                .map(|date| push_number(funge, date.weekday().num_days_from_monday().into())),
+            // Day of the year (0 is January 1st)
+            'Y' => pop_date(funge).map(|date| push_number(funge, date.ordinal0().into())),
+            _ => unreachable!("DATE does not provide {}", instruction),
+        };
+        if result.is_none() {
+            funge.reflect();
+        }
+        Ok(())
+    }
+}
+
+/// Pops a date, if it is a valid one.
+fn pop_date<T: FungeInteger>(funge: &mut dyn Context<T>) -> Option<NaiveDate> {
+    let (day, month, year) = (funge.pop(), funge.pop(), funge.pop());
+    NaiveDate::from_ymd_opt(year.to_i32()?, month.to_u32()?, day.to_u32()?)
+}
+
+fn push_date<T: FungeInteger>(funge: &mut dyn Context<T>, date: NaiveDate) {
+    push_number(funge, date.year().into());
+    push_number(funge, date.month().into());
+    push_number(funge, date.day().into());
+}
+
+fn push_number<T: FungeInteger>(funge: &mut dyn Context<T>, n: i64) {
+    funge.push(T::from(n).unwrap_or_default());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::program::fingerprints::execute_on;
+    use anyhow::ensure;
+
+    #[test]
+    fn test_add_days() -> Result<()> {
+        ensure!(execute_on(&Date, 'A', &[2022, 2, 4, 25])? == (vec![2022, 3, 1], false));
+        ensure!(execute_on(&Date, 'A', &[2022, 1, 1, -1])? == (vec![2021, 12, 31], false));
+        ensure!(execute_on(&Date, 'A', &[2022, 2, 30, 1])? == (vec![], true));
+        ensure!(execute_on(&Date, 'A', &[2022, 2, 4, i32::MAX])? == (vec![], true));
+        ensure!(execute_on(&Date, 'A', &[2022, 2, 4, i32::MIN])? == (vec![], true));
+        ensure!(execute_on(&Date, 'A', &[2022, 2, 4, i64::MAX])? == (vec![], true));
+        ensure!(execute_on(&Date, 'A', &[2022, 2, 4, i64::MIN])? == (vec![], true));
+        Ok(())
+    }
+
+    #[test]
+    fn test_julian_days() -> Result<()> {
+        ensure!(execute_on(&Date, 'J', &[2000, 1, 1])? == (vec![2451545], false));
+        ensure!(execute_on(&Date, 'C', &[2451545])? == (vec![2000, 1, 1], false));
+        ensure!(execute_on(&Date, 'C', &[i32::MIN])? == (vec![], true));
+        ensure!(execute_on(&Date, 'C', &[i64::MIN])? == (vec![], true));
+        ensure!(execute_on(&Date, 'C', &[i64::MAX])? == (vec![], true));
+        Ok(())
+    }
+
+    #[test]
+    fn test_days_between() -> Result<()> {
+        ensure!(execute_on(&Date, 'D', &[2022, 3, 1, 2022, 2, 4])? == (vec![25], false));
+        ensure!(execute_on(&Date, 'D', &[2022, 2, 4, 2022, 3, 1])? == (vec![-25], false));
+        Ok(())
+    }
+
+    #[test]
+    fn test_day_of_year() -> Result<()> {
+        ensure!(execute_on(&Date, 'Y', &[2020, 3, 1])? == (vec![60], false));
+        ensure!(execute_on(&Date, 'T', &[2020, 60])? == (vec![2020, 3, 1], false));
+        ensure!(execute_on(&Date, 'T', &[2021, 365])? == (vec![], true));
+        ensure!(execute_on(&Date, 'T', &[2021, -1])? == (vec![], true));
+        ensure!(execute_on(&Date, 'T', &[2021, u32::MAX as i64])? == (vec![], true));
+        Ok(())
+    }
+
+    #[test]
+    fn test_day_of_week() -> Result<()> {
+        // February 4th, 2022 was a Friday
+        ensure!(execute_on(&Date, 'W', &[2022, 2, 4])? == (vec![4], false));
+        Ok(())
+    }
+}
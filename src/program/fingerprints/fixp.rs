@@ -0,0 +1,261 @@
+use anyhow::Result;
+use num_traits::checked_pow;
+use rand::Rng;
+
+use super::{Context, Fingerprint, FungeInteger};
+
+/**
+`FIXP`: fixed point math.
+
+Angles are in degrees and, like the results of all trigonometric functions,
+are scaled by 10000 (e.g., the sine of 300000 i.e., 30 degrees, is 5000 i.e., 0.5).
+Results are rounded to the nearest integer; an undefined result (e.g., the square root
+of a negative number) reflects.
+*/
+pub(super) struct Fixp;
+
+/// The scale of fixed point numbers.
+const SCALE: f64 = 10000.0;
+
+impl<T: FungeInteger> Fingerprint<T> for Fixp {
+    fn name(&self) -> &'static str {
+        "FIXP"
+    }
+
+    fn instructions(&self) -> &'static str {
+        "ABCDEIJNOPQRSTUX"
+    }
+
+    fn execute(&self, instruction: char, funge: &mut dyn Context<T>) -> Result<()> {
+        match instruction {
+            // Bitwise operations: pop b and a, then push a op b
+            'A' | 'O' | 'X' => {
+                let (b, a) = (funge.pop(), funge.pop());
+                funge.push(match instruction {
+                    'A' => a & b,
+                    'O' => a | b,
+                    _ => a ^ b,
+                });
+            }
+            // Trigonometric functions of an angle
+            'C' => trigonometric(funge, f64::cos),
+            'I' => trigonometric(funge, f64::sin),
+            'T' => trigonometric(funge, f64::tan),
+            // Inverse trigonometric functions, resulting in an angle
+            'B' => inverse_trigonometric(funge, f64::acos),
+            'E' => inverse_trigonometric(funge, f64::asin),
+            'J' => inverse_trigonometric(funge, f64::atan),
+            // Random number: pop n, then push a random number in [0, n) (or (n, 0] if n < 0)
+            'D' => {
+                let n = funge.pop().to_i64().unwrap_or_default();
+                let r = match n {
+                    0 => 0,
+                    n if n > 0 => rand::thread_rng().gen_range(0..n),
+                    n => -(rand::thread_rng().gen_range(0..n.unsigned_abs()) as i64),
+                };
+                funge.push(T::from(r).unwrap_or_default());
+            }
+            // Negate, wrapping around like `-` does (the least cell is its own negation)
+            'N' => {
+                let a = funge.pop();
+                funge.push(T::zero().overflowing_sub(&a).0);
+            }
+            // Multiply by pi
+            'P' => {
+                let a = funge.pop().to_f64().unwrap_or_default();
+                push_rounded(funge, a * std::f64::consts::PI);
+            }
+            // Square root
+            'Q' => {
+                let a = funge.pop().to_f64().unwrap_or_default();
+                push_rounded(funge, a.sqrt());
+            }
+            // Power: pop b and a, then push a raised to the power of b
+            'R' => {
+                let (b, a) = (funge.pop(), funge.pop());
+                match power(a, b) {
+                    Some(p) => funge.push(p),
+                    None => funge.reflect(),
+                }
+            }
+            // Sign: -1, 0 or 1
+            'S' => {
+                let a = funge.pop();
+                funge.push(a.signum());
+            }
+            // Absolute value, wrapping around like `N` does
+            'U' => {
+                let a = funge.pop();
+                funge.push(if a < T::zero() {
+                    T::zero().overflowing_sub(&a).0
+                } else {
+                    a
+                });
+            }
+            _ => unreachable!("FIXP does not provide {}", instruction),
+        }
+        Ok(())
+    }
+}
+
+/// Pops an angle, then pushes `f` of it.
+fn trigonometric<T: FungeInteger>(funge: &mut dyn Context<T>, f: fn(f64) -> f64) {
+    let angle = funge.pop().to_f64().unwrap_or_default() / SCALE;
+    push_rounded(funge, f(angle.to_radians()) * SCALE);
+}
+
+/// Pops a number, then pushes the angle `f` results in.
+fn inverse_trigonometric<T: FungeInteger>(funge: &mut dyn Context<T>, f: fn(f64) -> f64) {
+    let x = funge.pop().to_f64().unwrap_or_default() / SCALE;
+    push_rounded(funge, f(x).to_degrees() * SCALE);
+}
+
+/// Pushes `x` rounded to the nearest integer, or reflects if `x` is not a (representable) number.
+fn push_rounded<T: FungeInteger>(funge: &mut dyn Context<T>, x: f64) {
+    match T::from(x.round()) {
+        Some(n) => funge.push(n),
+        None => funge.reflect(),
+    }
+}
+
+/// Returns `a` raised to the power of `b`, if it is an integer that fits in a cell.
+fn power<T: FungeInteger>(a: T, b: T) -> Option<T> {
+    if b >= T::zero() {
+        checked_pow(a, b.to_usize()?)
+    } else if a == T::one() {
+        Some(T::one())
+    } else if a == -T::one() {
+        // -1 to the power of an even/odd number
+        Some(if b % (T::one() + T::one()) == T::zero() {
+            T::one()
+        } else {
+            -T::one()
+        })
+    } else if a == T::zero() {
+        // division by 0
+        None
+    } else {
+        Some(T::zero())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::program::fingerprints::execute_on;
+    use anyhow::ensure;
+
+    #[test]
+    fn test_bitwise() -> Result<()> {
+        for (instruction, expected) in [('A', 0b1000), ('O', 0b1110), ('X', 0b0110)] {
+            ensure!(execute_on(&Fixp, instruction, &[0b1100, 0b1010])? == (vec![expected], false));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_cos() -> Result<()> {
+        ensure!(execute_on(&Fixp, 'C', &[600000])? == (vec![5000], false));
+        ensure!(execute_on(&Fixp, 'C', &[0])? == (vec![10000], false));
+        Ok(())
+    }
+
+    #[test]
+    fn test_sin() -> Result<()> {
+        ensure!(execute_on(&Fixp, 'I', &[300000])? == (vec![5000], false));
+        ensure!(execute_on(&Fixp, 'I', &[-900000])? == (vec![-10000], false));
+        Ok(())
+    }
+
+    #[test]
+    fn test_tan() -> Result<()> {
+        ensure!(execute_on(&Fixp, 'T', &[450000])? == (vec![10000], false));
+        // tan(90°) does not fit in a cell
+        ensure!(execute_on(&Fixp, 'T', &[900000])? == (vec![], true));
+        Ok(())
+    }
+
+    #[test]
+    fn test_arccos() -> Result<()> {
+        ensure!(execute_on(&Fixp, 'B', &[5000])? == (vec![600000], false));
+        ensure!(execute_on(&Fixp, 'B', &[20000])? == (vec![], true));
+        Ok(())
+    }
+
+    #[test]
+    fn test_arcsin() -> Result<()> {
+        ensure!(execute_on(&Fixp, 'E', &[5000])? == (vec![300000], false));
+        ensure!(execute_on(&Fixp, 'E', &[-20000])? == (vec![], true));
+        Ok(())
+    }
+
+    #[test]
+    fn test_arctan() -> Result<()> {
+        ensure!(execute_on(&Fixp, 'J', &[10000])? == (vec![450000], false));
+        Ok(())
+    }
+
+    #[test]
+    fn test_random() -> Result<()> {
+        for _ in 0..100 {
+            let (stack, reflected) = execute_on(&Fixp, 'D', &[10])?;
+            ensure!(!reflected && (0..10).contains(&stack[0]));
+            let (stack, reflected) = execute_on(&Fixp, 'D', &[-10])?;
+            ensure!(!reflected && (-9..=0).contains(&stack[0]));
+        }
+        ensure!(execute_on(&Fixp, 'D', &[0])? == (vec![0], false));
+        let (stack, reflected) = execute_on(&Fixp, 'D', &[i64::MIN])?;
+        ensure!(!reflected && stack[0] <= 0 && stack[0] > i64::MIN);
+        Ok(())
+    }
+
+    #[test]
+    fn test_negate() -> Result<()> {
+        ensure!(execute_on(&Fixp, 'N', &[42])? == (vec![-42], false));
+        ensure!(execute_on(&Fixp, 'N', &[i32::MAX])? == (vec![-i32::MAX], false));
+        ensure!(execute_on(&Fixp, 'N', &[i32::MIN])? == (vec![i32::MIN], false));
+        Ok(())
+    }
+
+    #[test]
+    fn test_multiply_by_pi() -> Result<()> {
+        ensure!(execute_on(&Fixp, 'P', &[10000])? == (vec![31416], false));
+        Ok(())
+    }
+
+    #[test]
+    fn test_sqrt() -> Result<()> {
+        ensure!(execute_on(&Fixp, 'Q', &[49])? == (vec![7], false));
+        ensure!(execute_on(&Fixp, 'Q', &[-1])? == (vec![], true));
+        Ok(())
+    }
+
+    #[test]
+    fn test_power() -> Result<()> {
+        ensure!(execute_on(&Fixp, 'R', &[3, 4])? == (vec![81], false));
+        ensure!(execute_on(&Fixp, 'R', &[-1, -3])? == (vec![-1], false));
+        ensure!(execute_on(&Fixp, 'R', &[2, -1])? == (vec![0], false));
+        ensure!(execute_on(&Fixp, 'R', &[0, -1])? == (vec![], true));
+        ensure!(execute_on(&Fixp, 'R', &[2, 31])? == (vec![], true));
+        ensure!(execute_on(&Fixp, 'R', &[-2, 31])? == (vec![i32::MIN], false));
+        ensure!(execute_on(&Fixp, 'R', &[2, i32::MAX])? == (vec![], true));
+        ensure!(execute_on(&Fixp, 'R', &[-1, i32::MIN])? == (vec![1], false));
+        Ok(())
+    }
+
+    #[test]
+    fn test_sign() -> Result<()> {
+        for (n, sign) in [(-7, -1), (0, 0), (7, 1)] {
+            ensure!(execute_on(&Fixp, 'S', &[n])? == (vec![sign], false));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_abs() -> Result<()> {
+        ensure!(execute_on(&Fixp, 'U', &[-7])? == (vec![7], false));
+        ensure!(execute_on(&Fixp, 'U', &[7])? == (vec![7], false));
+        ensure!(execute_on(&Fixp, 'U', &[i32::MIN])? == (vec![i32::MIN], false));
+        Ok(())
+    }
+}
@@ -0,0 +1,286 @@
+use anyhow::Result;
+use num_traits::{Float, NumCast};
+use std::{fmt::Display, str::FromStr};
+
+use super::{Context, Fingerprint, FungeInteger};
+
+/**
+`FPSP`: single precision floating point arithmetic.
+
+Each float occupies one cell, which holds its bits (sign extended, for cells wider than 32 bits).
+*/
+pub(super) struct Fpsp;
+
+/**
+`FPDP`: double precision floating point arithmetic.
+
+Each double occupies two cells, each holding 32 of its bits (sign extended, for cells wider than 32 bits):
+the cell with the most significant bits is pushed first.
+*/
+pub(super) struct Fpdp;
+
+const INSTRUCTIONS: &str = "ABCDEFGHIKLMNPQRSTVXY";
+
+impl<T: FungeInteger> Fingerprint<T> for Fpsp {
+    fn name(&self) -> &'static str {
+        "FPSP"
+    }
+
+    fn instructions(&self) -> &'static str {
+        INSTRUCTIONS
+    }
+
+    fn execute(&self, instruction: char, funge: &mut dyn Context<T>) -> Result<()> {
+        execute::<T, f32>(instruction, funge)
+    }
+}
+
+impl<T: FungeInteger> Fingerprint<T> for Fpdp {
+    fn name(&self) -> &'static str {
+        "FPDP"
+    }
+
+    fn instructions(&self) -> &'static str {
+        INSTRUCTIONS
+    }
+
+    fn execute(&self, instruction: char, funge: &mut dyn Context<T>) -> Result<()> {
+        execute::<T, f64>(instruction, funge)
+    }
+}
+
+/// A floating point type that is packed into (a fixed number of) cells.
+trait Packed: Float + FromStr + Display {
+    /// Pops the cells of a packed float and returns the float.
+    fn pop_from<T: FungeInteger>(funge: &mut dyn Context<T>) -> Self;
+    /// Pushes the cells of the packed `self`.
+    fn push_onto<T: FungeInteger>(self, funge: &mut dyn Context<T>);
+}
+
+impl Packed for f32 {
+    fn pop_from<T: FungeInteger>(funge: &mut dyn Context<T>) -> Self {
+        f32::from_bits(low_bits(funge.pop()))
+    }
+
+    fn push_onto<T: FungeInteger>(self, funge: &mut dyn Context<T>) {
+        funge.push(cell(self.to_bits()));
+    }
+}
+
+impl Packed for f64 {
+    fn pop_from<T: FungeInteger>(funge: &mut dyn Context<T>) -> Self {
+        let (low, high) = (low_bits(funge.pop()), low_bits(funge.pop()));
+        f64::from_bits(((high as u64) << 32) | low as u64)
+    }
+
+    fn push_onto<T: FungeInteger>(self, funge: &mut dyn Context<T>) {
+        let bits = self.to_bits();
+        funge.push(cell((bits >> 32) as u32));
+        funge.push(cell(bits as u32));
+    }
+}
+
+/// Returns the 32 least significant bits of `cell`.
+fn low_bits<T: FungeInteger>(cell: T) -> u32 {
+    cell.to_i64().unwrap_or_default() as u32
+}
+
+/// Returns a cell holding `bits`, sign extended if the cell is wider than 32 bits.
+fn cell<T: FungeInteger>(bits: u32) -> T {
+    T::from(bits as i32).unwrap_or_default()
+}
+
+/// Executes one of the (common) instructions of `FPSP` and `FPDP`, with floats of type `F`.
+fn execute<T: FungeInteger, F: Packed>(
+    instruction: char,
+    funge: &mut dyn Context<T>,
+) -> Result<()> {
+    match instruction {
+        // Binary operations: pop b and a, then push a op b
+        'A' | 'D' | 'M' | 'S' | 'Y' => {
+            let (b, a) = (F::pop_from(funge), F::pop_from(funge));
+            let result = match instruction {
+                'A' => a + b,
+                'D' => a / b,
+                'M' => a * b,
+                'S' => a - b,
+                _ => a.powf(b),
+            };
+            result.push_onto(funge);
+        }
+        // Convert integer to float
+        'F' => {
+            let n = funge.pop();
+            <F as NumCast>::from(n)
+                .unwrap_or_else(F::nan)
+                .push_onto(funge);
+        }
+        // Convert float to integer (rounding towards 0, saturating on overflow)
+        'I' => {
+            let f = F::pop_from(funge);
+            let n = T::from(f.trunc()).unwrap_or_else(|| {
+                if f.is_nan() {
+                    T::zero()
+                } else if f > F::zero() {
+                    T::max_value().unwrap_or_default()
+                } else {
+                    T::min_value().unwrap_or_default()
+                }
+            });
+            funge.push(n);
+        }
+        // Print float, followed by a space
+        'P' => {
+            let f = F::pop_from(funge);
+            funge.print(&format!("{:.6} ", f))?;
+        }
+        // Parse a 0"gnirts as a float; reflect if it is not a number
+        'R' => {
+            let s = funge.pop_gnirts();
+            match s.trim().parse::<F>() {
+                Ok(f) => f.push_onto(funge),
+                Err(_) => funge.reflect(),
+            }
+        }
+        // Unary operations: pop a, then push op(a)
+        _ => {
+            let a = F::pop_from(funge);
+            let result = match instruction {
+                'B' => a.sin(),
+                'C' => a.cos(),
+                'E' => a.asin(),
+                'G' => a.atan(),
+                'H' => a.acos(),
+                'K' => a.ln(),
+                'L' => a.log10(),
+                'N' => -a,
+                'Q' => a.sqrt(),
+                'T' => a.tan(),
+                'V' => a.abs(),
+                'X' => a.exp(),
+                _ => unreachable!("FPSP/FPDP do not provide {}", instruction),
+            };
+            result.push_onto(funge);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::program::{fingerprints::execute_on, Program};
+    use anyhow::ensure;
+
+    fn single(f: f32) -> i32 {
+        f.to_bits() as i32
+    }
+
+    fn double(f: f64) -> [i32; 2] {
+        let bits = f.to_bits();
+        [(bits >> 32) as u32 as i32, bits as u32 as i32]
+    }
+
+    #[test]
+    fn test_packing_in_wide_cells() -> Result<()> {
+        let mut program = Program::<i64>::default();
+        for f in [1.5f32, -2.25, f32::MAX, f32::MIN_POSITIVE] {
+            f.push_onto(&mut program);
+            ensure!(f32::pop_from(&mut program) == f);
+        }
+        for f in [1.5f64, -2.25, f64::MAX, f64::MIN_POSITIVE] {
+            f.push_onto(&mut program);
+            ensure!(f64::pop_from(&mut program) == f);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fpsp_arithmetic() -> Result<()> {
+        let (a, b) = (single(7.5), single(2.5));
+        for (instruction, expected) in [('A', 10.0), ('S', 5.0), ('M', 18.75), ('D', 3.0)] {
+            ensure!(execute_on(&Fpsp, instruction, &[a, b])? == (vec![single(expected)], false));
+        }
+        ensure!(execute_on(&Fpsp, 'Y', &[b, single(2.0)])? == (vec![single(6.25)], false));
+        ensure!(execute_on(&Fpsp, 'Q', &[single(6.25)])? == (vec![b], false));
+        ensure!(execute_on(&Fpsp, 'N', &[b])? == (vec![single(-2.5)], false));
+        ensure!(execute_on(&Fpsp, 'V', &[single(-2.5)])? == (vec![b], false));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fpsp_out_of_range() -> Result<()> {
+        let (one, zero) = (single(1.0), single(0.0));
+        ensure!(execute_on(&Fpsp, 'D', &[one, zero])? == (vec![single(f32::INFINITY)], false));
+        ensure!(
+            execute_on(&Fpsp, 'M', &[single(f32::MAX), single(2.0)])?
+                == (vec![single(f32::INFINITY)], false)
+        );
+        let (stack, reflected) = execute_on(&Fpsp, 'Q', &[single(-1.0)])?;
+        ensure!(!reflected && f32::from_bits(stack[0] as u32).is_nan());
+        let (stack, reflected) = execute_on(&Fpsp, 'K', &[zero])?;
+        ensure!(!reflected && stack == vec![single(f32::NEG_INFINITY)]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fpsp_conversions() -> Result<()> {
+        ensure!(execute_on(&Fpsp, 'F', &[-3])? == (vec![single(-3.0)], false));
+        ensure!(execute_on(&Fpsp, 'F', &[i32::MIN])? == (vec![single(-2147483648.0)], false));
+        ensure!(execute_on(&Fpsp, 'I', &[single(-3.75)])? == (vec![-3], false));
+        ensure!(execute_on(&Fpsp, 'I', &[single(f32::NAN)])? == (vec![0], false));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_saturating_conversions() -> Result<()> {
+        for (f, n) in [
+            (1e20, i32::MAX),
+            (-1e20, i32::MIN),
+            (f32::INFINITY, i32::MAX),
+            (f32::NEG_INFINITY, i32::MIN),
+        ] {
+            ensure!(execute_on(&Fpsp, 'I', &[single(f)])? == (vec![n], false));
+        }
+        // with cells wider than 32 bits, the cells of a double are sign extended
+        let cells = double(f64::NEG_INFINITY).map(|cell| cell as i64);
+        ensure!(execute_on(&Fpdp, 'I', &cells)? == (vec![i64::MIN], false));
+        ensure!(execute_on(&Fpdp, 'I', &double(1e300))? == (vec![i32::MAX], false));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fpsp_parse() -> Result<()> {
+        ensure!(execute_on(&Fpsp, 'R', &[0, 53, 46, 50, 45])? == (vec![single(-2.5)], false));
+        ensure!(execute_on(&Fpsp, 'R', &[0, 120])? == (vec![], true));
+        ensure!(execute_on(&Fpsp, 'R', &[0])? == (vec![], true));
+        // "1e99" does not fit in a single, so it parses as infinity
+        ensure!(
+            execute_on(&Fpsp, 'R', &[0, 57, 57, 101, 49])? == (vec![single(f32::INFINITY)], false)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fpdp() -> Result<()> {
+        let (a, b) = (double(7.5), double(2.5));
+        ensure!(execute_on(&Fpdp, 'A', &[a, b].concat())? == (double(10.0).to_vec(), false));
+        ensure!(execute_on(&Fpdp, 'D', &[a, b].concat())? == (double(3.0).to_vec(), false));
+        ensure!(execute_on(&Fpdp, 'F', &[-3])? == (double(-3.0).to_vec(), false));
+        ensure!(execute_on(&Fpdp, 'F', &[i32::MIN])? == (double(-2147483648.0).to_vec(), false));
+        ensure!(execute_on(&Fpdp, 'I', &double(-3.75))? == (vec![-3], false));
+        ensure!(execute_on(&Fpdp, 'X', &double(0.0))? == (double(1.0).to_vec(), false));
+        ensure!(
+            execute_on(&Fpdp, 'X', &double(1000.0))? == (double(f64::INFINITY).to_vec(), false)
+        );
+        ensure!(execute_on(&Fpdp, 'K', &double(1.0))? == (double(0.0).to_vec(), false));
+
+        Ok(())
+    }
+}
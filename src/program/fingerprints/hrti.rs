@@ -0,0 +1,161 @@
+use anyhow::Result;
+use chrono::{Duration, Timelike};
+
+use super::{Context, Fingerprint, FungeInteger};
+
+/**
+`HRTI`: high resolution timer, with a granularity of 1 microsecond.
+
+Every IP has its own timer mark.
+*/
+pub(super) struct Hrti;
+
+impl<T: FungeInteger> Fingerprint<T> for Hrti {
+    fn name(&self) -> &'static str {
+        "HRTI"
+    }
+
+    fn instructions(&self) -> &'static str {
+        "EGMST"
+    }
+
+    fn execute(&self, instruction: char, funge: &mut dyn Context<T>) -> Result<()> {
+        match instruction {
+            // Erase the timer mark
+            'E' => *funge.timer_mark() = None,
+            // Granularity of the timer, in microseconds
+            'G' => funge.push(T::one()),
+            // Mark the timer
+            'M' => *funge.timer_mark() = Some(funge.now()),
+            // Microseconds since the last whole second
+            'S' => {
+                let micros = funge.now().nanosecond() / 1000;
+                funge.push(T::from(micros).unwrap_or_default());
+            }
+            // Microseconds since the timer was marked (saturating if they do not fit in a cell);
+            // reflect if it is not marked
+            'T' => match *funge.timer_mark() {
+                Some(mark) => {
+                    let elapsed = funge.now() - mark;
+                    let micros = elapsed.num_microseconds().and_then(T::from);
+                    let micros = micros.or_else(if elapsed < Duration::zero() {
+                        T::min_value
+                    } else {
+                        T::max_value
+                    });
+                    funge.push(micros.unwrap_or_default());
+                }
+                None => funge.reflect(),
+            },
+            _ => unreachable!("HRTI does not provide {}", instruction),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::program::{clock::Clock, Program};
+    use anyhow::ensure;
+    use chrono::{DateTime, Duration, TimeZone, Utc};
+    use std::{cell::Cell, rc::Rc};
+
+    /// A clock that only moves when it is told to.
+    struct ManualClock(Cell<DateTime<Utc>>);
+
+    impl Clock for ManualClock {
+        fn now(&self) -> DateTime<Utc> {
+            self.0.get()
+        }
+    }
+
+    fn program_with_manual_clock() -> (Program<i32>, Rc<ManualClock>) {
+        let clock = Rc::new(ManualClock(Cell::new(
+            Utc.ymd(2022, 2, 4).and_hms_micro(12, 0, 0, 250),
+        )));
+        let program = Program::<i32>::builder("").clock(clock.clone()).build();
+        (program, clock)
+    }
+
+    fn toss(program: &Program<i32>) -> Vec<i32> {
+        program
+            .ip()
+            .sstack
+            .get_stacks()
+            .first()
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn test_granularity() -> Result<()> {
+        let (mut program, _) = program_with_manual_clock();
+        Hrti.execute('G', &mut program)?;
+        ensure!(toss(&program) == vec![1]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_mark_and_elapsed() -> Result<()> {
+        let (mut program, clock) = program_with_manual_clock();
+        Hrti.execute('M', &mut program)?;
+        clock.0.set(clock.now() + Duration::microseconds(1234));
+        Hrti.execute('T', &mut program)?;
+        ensure!(toss(&program) == vec![1234]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_elapsed_out_of_range() -> Result<()> {
+        let (mut program, clock) = program_with_manual_clock();
+        Hrti.execute('M', &mut program)?;
+        // more microseconds than fit in a cell saturate
+        clock.0.set(clock.now() + Duration::days(1));
+        Hrti.execute('T', &mut program)?;
+        ensure!(toss(&program) == vec![i32::MAX]);
+        // a clock that went back in time gives a negative time
+        clock
+            .0
+            .set(clock.now() - Duration::days(1) - Duration::microseconds(5));
+        Hrti.execute('T', &mut program)?;
+        ensure!(toss(&program) == vec![i32::MAX, -5]);
+        clock.0.set(clock.now() - Duration::days(1));
+        Hrti.execute('T', &mut program)?;
+        ensure!(toss(&program) == vec![i32::MAX, -5, i32::MIN]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_erase() -> Result<()> {
+        let (mut program, _) = program_with_manual_clock();
+        // an unmarked timer reflects
+        Hrti.execute('T', &mut program)?;
+        ensure!(program.ip().cursor.delta().x < 0);
+        Hrti.execute('M', &mut program)?;
+        Hrti.execute('E', &mut program)?;
+        Hrti.execute('T', &mut program)?;
+        ensure!(program.ip().cursor.delta().x > 0);
+        ensure!(toss(&program).is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_second_fraction() -> Result<()> {
+        let (mut program, _) = program_with_manual_clock();
+        Hrti.execute('S', &mut program)?;
+        ensure!(toss(&program) == vec![250]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_marks_per_ip() -> Result<()> {
+        let (mut program, _) = program_with_manual_clock();
+        Hrti.execute('M', &mut program)?;
+        let child = program.ip().split(1);
+        ensure!(child.timer_mark.is_some());
+        program.ip_mut().timer_mark = None;
+        ensure!(child.timer_mark.is_some());
+        Ok(())
+    }
+}
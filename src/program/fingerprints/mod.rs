@@ -0,0 +1,325 @@
+use anyhow::{Context as _, Result};
+use chrono::{DateTime, Utc};
+use std::{collections::HashMap, rc::Rc};
+
+use super::{cursor::Cursor, fungetypes::FungeInteger, sstack::SStack, Program};
+
+mod boolean;
+mod date;
+mod fixp;
+mod floats;
+mod hrti;
+mod modu;
+mod null;
+mod refc;
+mod roma;
+mod strn;
+
+/**
+The view of a running program that fingerprints get when executing
+one of their instructions: the stack stack and the cursor of the current IP,
+as well as (the shared) Funge-Space.
+*/
+#[allow(dead_code)] // not every fingerprint needs every part of the context
+pub(super) trait Context<T: FungeInteger> {
+    /// Pushes `x` onto the TOSS.
+    fn push(&mut self, x: T);
+    /// Pops and returns a value from the TOSS, or 0 if it is empty.
+    fn pop(&mut self) -> T;
+    /// Pushes the vector `v` onto the TOSS, one component per dimension.
+    fn push_vector(&mut self, v: (T, T, T));
+    /// Pops and returns a vector from the TOSS, one component per dimension.
+    fn pop_vector(&mut self) -> (T, T, T);
+    /// Pops a null-terminated `0"gnirts` from the TOSS and returns it.
+    fn pop_gnirts(&mut self) -> String;
+    /// Pushes `s` onto the TOSS as a null-terminated `0"gnirts`.
+    fn push_gnirts(&mut self, s: &str);
+    /// The stack stack of the current IP.
+    fn sstack(&mut self) -> &mut SStack<T>;
+    /// The cursor of the current IP.
+    fn cursor(&self) -> &Cursor<T>;
+    /// The cursor of the current IP, mutably.
+    fn cursor_mut(&mut self) -> &mut Cursor<T>;
+    /// Returns the cell on `position`, or a space if it is out of bounds.
+    fn get_cell(&self, position: (T, T, T)) -> T;
+    /// Puts `c` on `position`, resizing Funge-Space if necessary.
+    fn put_cell(&mut self, position: (T, T, T), c: T);
+    /// Returns `true` if `position` is out of the bounds of Funge-Space.
+    fn out_of_bounds(&self, position: (T, T, T)) -> bool;
+    /// Writes `s` to the output of the program.
+    fn print(&mut self, s: &str) -> Result<()>;
+    /// Reads a line (including its EOL, if any) from the input of the program.
+    fn read_line(&mut self) -> Result<String>;
+    /// Returns the current time, as read from the clock of the program.
+    fn now(&self) -> DateTime<Utc>;
+    /// The mark of the `HRTI` timer of the current IP.
+    fn timer_mark(&mut self) -> &mut Option<DateTime<Utc>>;
+    /// Stores `v` in the vector storage of the program and returns its handle.
+    fn reference(&mut self, v: (T, T, T)) -> T;
+    /// Returns the vector stored under `handle`, if there is one.
+    fn dereference(&self, handle: T) -> Option<(T, T, T)>;
+
+    /// Reflects the cursor of the current IP.
+    fn reflect(&mut self) {
+        self.cursor_mut().reflect();
+    }
+}
+
+impl<T: FungeInteger> Context<T> for Program<T> {
+    fn push(&mut self, x: T) {
+        Program::push(self, x);
+    }
+
+    fn pop(&mut self) -> T {
+        Program::pop(self)
+    }
+
+    fn push_vector(&mut self, v: (T, T, T)) {
+        Program::push_vector(self, v);
+    }
+
+    fn pop_vector(&mut self) -> (T, T, T) {
+        Program::pop_vector(self)
+    }
+
+    fn pop_gnirts(&mut self) -> String {
+        Program::pop_gnirts(self)
+    }
+
+    fn push_gnirts(&mut self, s: &str) {
+        Program::push_gnirts(self, s);
+    }
+
+    fn sstack(&mut self) -> &mut SStack<T> {
+        &mut self.ip_mut().sstack
+    }
+
+    fn cursor(&self) -> &Cursor<T> {
+        &self.ip().cursor
+    }
+
+    fn cursor_mut(&mut self) -> &mut Cursor<T> {
+        &mut self.ip_mut().cursor
+    }
+
+    fn get_cell(&self, position: (T, T, T)) -> T {
+        Program::get_cell(self, position)
+    }
+
+    fn put_cell(&mut self, position: (T, T, T), c: T) {
+        Program::put_cell(self, position, c);
+    }
+
+    fn out_of_bounds(&self, position: (T, T, T)) -> bool {
+        self.grid.out_of_bounds(position)
+    }
+
+    fn print(&mut self, s: &str) -> Result<()> {
+        self.streams
+            .print(s)
+            .context("Failed to write fingerprint output to stdout")
+    }
+
+    fn read_line(&mut self) -> Result<String> {
+        let line = self
+            .streams
+            .read_line()
+            .context("Failed while reading a line from stdin")?;
+        Ok(line.unwrap_or_default())
+    }
+
+    fn now(&self) -> DateTime<Utc> {
+        self.clock.now()
+    }
+
+    fn timer_mark(&mut self) -> &mut Option<DateTime<Utc>> {
+        &mut self.ip_mut().timer_mark
+    }
+
+    fn reference(&mut self, v: (T, T, T)) -> T {
+        self.references.push(v);
+        T::from(self.references.len() - 1).unwrap_or_default()
+    }
+
+    fn dereference(&self, handle: T) -> Option<(T, T, T)> {
+        handle
+            .to_usize()
+            .and_then(|i| self.references.get(i))
+            .cloned()
+    }
+}
+
+/**
+A fingerprint i.e., a named set of semantics for (some of) the `A`-`Z` instructions,
+that can be loaded with `(` and unloaded with `)`.
+*/
+pub(super) trait Fingerprint<T: FungeInteger> {
+    /// The name of the fingerprint e.g., `"NULL"`.
+    fn name(&self) -> &'static str;
+
+    /// The instructions (out of `A`-`Z`) the fingerprint provides semantics for.
+    fn instructions(&self) -> &'static str;
+
+    /**
+    Executes `instruction`, which is one of the fingerprint's `instructions`.
+
+    Errors are only returned for failures of the interpreter itself (e.g., I/O);
+    failures that the Funge-98 program should be notified of reflect the IP instead.
+    */
+    fn execute(&self, instruction: char, funge: &mut dyn Context<T>) -> Result<()>;
+
+    /// The fingerprint ID i.e., the name of the fingerprint in base 256.
+    fn id(&self) -> i64 {
+        self.name().bytes().fold(0, |id, b| id * 256 + b as i64)
+    }
+}
+
+/// All the fingerprints that programs can load, keyed by their IDs.
+pub(super) struct Registry<T: FungeInteger> {
+    fingerprints: HashMap<i64, Rc<dyn Fingerprint<T>>>,
+}
+
+impl<T: FungeInteger> Registry<T> {
+    /// Makes `fingerprint` loadable, replacing any fingerprint with the same ID.
+    pub fn register(&mut self, fingerprint: Rc<dyn Fingerprint<T>>) {
+        self.fingerprints.insert(fingerprint.id(), fingerprint);
+    }
+
+    /// Returns the fingerprint with ID `id`, if it is registered.
+    pub fn get(&self, id: &T) -> Option<Rc<dyn Fingerprint<T>>> {
+        self.fingerprints.get(&id.to_i64()?).cloned()
+    }
+}
+
+impl<T: FungeInteger> Default for Registry<T> {
+    /// Creates a registry with all the fingerprints `bff` implements.
+    fn default() -> Self {
+        let mut registry = Self {
+            fingerprints: HashMap::new(),
+        };
+        registry.register(Rc::new(boolean::Bool));
+        registry.register(Rc::new(date::Date));
+        registry.register(Rc::new(fixp::Fixp));
+        registry.register(Rc::new(floats::Fpdp));
+        registry.register(Rc::new(floats::Fpsp));
+        registry.register(Rc::new(hrti::Hrti));
+        registry.register(Rc::new(modu::Modu));
+        registry.register(Rc::new(null::Null));
+        registry.register(Rc::new(refc::Refc));
+        registry.register(Rc::new(roma::Roma));
+        registry.register(Rc::new(strn::Strn));
+        registry
+    }
+}
+
+/**
+Executes `instruction` of `fingerprint` on a fresh program whose TOSS is `stack`
+(pushed from left to right) and returns the resulting TOSS, as well as
+whether the instruction reflected the IP.
+*/
+#[cfg(test)]
+fn execute_on<T: FungeInteger>(
+    fingerprint: &dyn Fingerprint<T>,
+    instruction: char,
+    stack: &[T],
+) -> Result<(Vec<T>, bool)> {
+    let mut program = Program::<T>::default();
+    for x in stack {
+        program.push(x.clone());
+    }
+    fingerprint.execute(instruction, &mut program)?;
+    let reflected = program.ip().cursor.delta().x < T::zero();
+    Ok((program.ip().sstack.get_stacks()[0].clone(), reflected))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::program::ip::InstructionPointer;
+    use anyhow::ensure;
+
+    /// Provides `A`, which pushes `value`, and `B`, which reflects.
+    struct Dummy {
+        name: &'static str,
+        value: i32,
+    }
+
+    impl Fingerprint<i32> for Dummy {
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        fn instructions(&self) -> &'static str {
+            "AB"
+        }
+
+        fn execute(&self, instruction: char, funge: &mut dyn Context<i32>) -> Result<()> {
+            match instruction {
+                'A' => funge.push(self.value),
+                _ => funge.reflect(),
+            }
+            Ok(())
+        }
+    }
+
+    fn program_with_dummies(code: &str) -> Program<i32> {
+        let mut program = Program::<i32>::from(vec![code.as_bytes().to_vec()]);
+        for (name, value) in [("DMYA", 1), ("DMYB", 2)] {
+            program
+                .fingerprints
+                .register(Rc::new(Dummy { name, value }));
+        }
+        program
+    }
+
+    #[test]
+    fn test_load_pushes_id_and_one() -> Result<()> {
+        let mut program = program_with_dummies("\"AYMD\"4(A0q");
+        program.run()?;
+        ensure!(program.ip().sstack.get_stacks()[0] == vec![0x444d5941, 1, 1]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_unknown_fingerprint_reflects() -> Result<()> {
+        // the reflected IP wraps around and pushes 5 before quitting
+        let mut program = program_with_dummies("\"KNUF\"4(q05");
+        program.run()?;
+        ensure!(program.ip().sstack.get_stacks()[0].last() == Some(&5));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_semantics_overlay_and_unload() -> Result<()> {
+        let mut ip = InstructionPointer::<i32>::default();
+        let a: Rc<dyn Fingerprint<i32>> = Rc::new(Dummy {
+            name: "DMYA",
+            value: 1,
+        });
+        let b: Rc<dyn Fingerprint<i32>> = Rc::new(Dummy {
+            name: "DMYB",
+            value: 2,
+        });
+        let top = |ip: &InstructionPointer<i32>| ip.semantics('A').map(|fp| fp.name());
+
+        ensure!(top(&ip).is_none());
+        ip.load_semantics(Rc::clone(&a));
+        ip.load_semantics(Rc::clone(&b));
+        ensure!(top(&ip) == Some("DMYB"));
+        ip.unload_semantics(b.as_ref());
+        ensure!(top(&ip) == Some("DMYA"));
+        ip.unload_semantics(a.as_ref());
+        ensure!(top(&ip).is_none());
+
+        // unloading pops the semantics, regardless of the fingerprint they came from
+        ip.load_semantics(Rc::clone(&a));
+        ip.load_semantics(Rc::clone(&b));
+        ip.unload_semantics(a.as_ref());
+        ensure!(top(&ip) == Some("DMYA"));
+        ensure!(ip.semantics('C').is_none());
+
+        Ok(())
+    }
+}
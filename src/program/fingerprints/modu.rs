@@ -0,0 +1,112 @@
+use anyhow::Result;
+
+use super::{Context, Fingerprint, FungeInteger};
+
+/**
+`MODU`: the modulo operations that `%` does not provide.
+
+All of them pop b and a, then push a "mod" b; a remainder with a divisor of 0 is 0,
+exactly like with `%`, and so is a remainder with a divisor of -1, even for the least cell.
+*/
+pub(super) struct Modu;
+
+impl<T: FungeInteger> Fingerprint<T> for Modu {
+    fn name(&self) -> &'static str {
+        "MODU"
+    }
+
+    fn instructions(&self) -> &'static str {
+        "MRU"
+    }
+
+    fn execute(&self, instruction: char, funge: &mut dyn Context<T>) -> Result<()> {
+        let (b, a) = (funge.pop(), funge.pop());
+        if b == T::zero() {
+            funge.push(T::zero());
+            return Ok(());
+        }
+        // the remainder of the least cell divided by -1 is 0, even though the division overflows
+        let remainder = a.overflowing_rem(&b).0;
+        funge.push(match instruction {
+            // signed-result modulo: the result has the sign of the divisor
+            'M' => {
+                if remainder != T::zero() && (remainder < T::zero()) != (b < T::zero()) {
+                    remainder + b
+                } else {
+                    remainder
+                }
+            }
+            // unsigned-result modulo (as defined by Sam Holden)
+            'U' => remainder.abs(),
+            // C-language integer remainder: the result has the sign of the dividend
+            'R' => remainder,
+            _ => unreachable!("MODU does not provide {}", instruction),
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::program::{fingerprints::execute_on, Dialect, Program};
+    use anyhow::ensure;
+
+    const CASES: [(i32, i32); 4] = [(7, 3), (-7, 3), (7, -3), (-7, -3)];
+
+    #[test]
+    fn test_signed_result() -> Result<()> {
+        for ((a, b), expected) in CASES.into_iter().zip([1, 2, -2, -1]) {
+            ensure!(execute_on(&Modu, 'M', &[a, b])? == (vec![expected], false));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_unsigned_result() -> Result<()> {
+        for ((a, b), expected) in CASES.into_iter().zip([1, 1, 1, 1]) {
+            ensure!(execute_on(&Modu, 'U', &[a, b])? == (vec![expected], false));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_c_remainder() -> Result<()> {
+        for ((a, b), expected) in CASES.into_iter().zip([1, -1, 1, -1]) {
+            ensure!(execute_on(&Modu, 'R', &[a, b])? == (vec![expected], false));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_zero_divisor() -> Result<()> {
+        for instruction in ['M', 'U', 'R'] {
+            ensure!(execute_on(&Modu, instruction, &[7, 0])? == (vec![0], false));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_least_dividend() -> Result<()> {
+        for instruction in ['M', 'U', 'R'] {
+            ensure!(execute_on(&Modu, instruction, &[i32::MIN, -1])? == (vec![0], false));
+            ensure!(execute_on(&Modu, instruction, &[i32::MIN, i32::MIN])? == (vec![0], false));
+        }
+        ensure!(execute_on(&Modu, 'M', &[i32::MIN, i32::MAX])? == (vec![i32::MAX - 1], false));
+        ensure!(execute_on(&Modu, 'U', &[i32::MIN, 3])? == (vec![2], false));
+        Ok(())
+    }
+
+    #[test]
+    fn test_least_dividend_of_the_core_instructions() -> Result<()> {
+        // `/` wraps around and `%` gives 0 too, instead of overflowing
+        for (source, expected) in [(b"/", i32::MIN), (b"%", 0)] {
+            let mut program = Program::<i32>::from_source(source, Dialect::Befunge);
+            program.push(i32::MIN);
+            program.push(-1);
+            program.step()?;
+            ensure!(program.ip().sstack.get_stacks()[0] == vec![expected]);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,36 @@
+use anyhow::Result;
+
+use super::{Context, Fingerprint, FungeInteger};
+
+/// `NULL`: every instruction `A`-`Z` reflects.
+pub(super) struct Null;
+
+impl<T: FungeInteger> Fingerprint<T> for Null {
+    fn name(&self) -> &'static str {
+        "NULL"
+    }
+
+    fn instructions(&self) -> &'static str {
+        "ABCDEFGHIJKLMNOPQRSTUVWXYZ"
+    }
+
+    fn execute(&self, _instruction: char, funge: &mut dyn Context<T>) -> Result<()> {
+        funge.reflect();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::program::fingerprints::execute_on;
+    use anyhow::ensure;
+
+    #[test]
+    fn test_everything_reflects() -> Result<()> {
+        for instruction in 'A'..='Z' {
+            ensure!(execute_on(&Null, instruction, &[1, 2])? == (vec![1, 2], true));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,96 @@
+use anyhow::Result;
+
+use super::{Context, Fingerprint, FungeInteger};
+
+/**
+`REFC`: references i.e., vectors stored behind scalar handles.
+
+The stored vectors belong to the program, so a handle created by an IP
+can be dereferenced by any other IP.
+*/
+pub(super) struct Refc;
+
+impl<T: FungeInteger> Fingerprint<T> for Refc {
+    fn name(&self) -> &'static str {
+        "REFC"
+    }
+
+    fn instructions(&self) -> &'static str {
+        "DR"
+    }
+
+    fn execute(&self, instruction: char, funge: &mut dyn Context<T>) -> Result<()> {
+        match instruction {
+            // Dereference a handle to its vector; reflect if there is no such handle
+            'D' => {
+                let handle = funge.pop();
+                match funge.dereference(handle) {
+                    Some(v) => funge.push_vector(v),
+                    None => funge.reflect(),
+                }
+            }
+            // Reference a vector, pushing its new handle
+            'R' => {
+                let v = funge.pop_vector();
+                let handle = funge.reference(v);
+                funge.push(handle);
+            }
+            _ => unreachable!("REFC does not provide {}", instruction),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::program::{fingerprints::execute_on, Program};
+    use anyhow::ensure;
+
+    #[test]
+    fn test_reference_and_dereference() -> Result<()> {
+        let mut program = Program::<i32>::default();
+        program.push_vector((1, 2, 0));
+        Refc.execute('R', &mut program)?;
+        program.push_vector((3, 4, 0));
+        Refc.execute('R', &mut program)?;
+        Refc.execute('D', &mut program)?;
+        ensure!(program.ip().sstack.get_stacks()[0] == vec![0, 3, 4]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_references_are_shared_among_ips() -> Result<()> {
+        let mut program = Program::<i32>::default();
+        program.push_vector((7, -8, 0));
+        Refc.execute('R', &mut program)?;
+        let handle = program.pop();
+        program.ips.push(program.ip().split(1));
+        program.current_ip = 1;
+        program.push(handle);
+        Refc.execute('D', &mut program)?;
+        ensure!(program.ip().sstack.get_stacks()[0] == vec![7, -8]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_unknown_handle_reflects() -> Result<()> {
+        ensure!(execute_on(&Refc, 'D', &[0])? == (vec![], true));
+        ensure!(execute_on(&Refc, 'D', &[-1])? == (vec![], true));
+        ensure!(execute_on(&Refc, 'D', &[i32::MAX])? == (vec![], true));
+        ensure!(execute_on(&Refc, 'D', &[i32::MIN])? == (vec![], true));
+        // a handle wider than a `usize`
+        ensure!(execute_on(&Refc, 'D', &[i128::MAX])? == (vec![], true));
+        Ok(())
+    }
+
+    #[test]
+    fn test_extreme_vectors() -> Result<()> {
+        let mut program = Program::<i32>::default();
+        program.push_vector((i32::MIN, i32::MAX, 0));
+        Refc.execute('R', &mut program)?;
+        Refc.execute('D', &mut program)?;
+        ensure!(program.ip().sstack.get_stacks()[0] == vec![i32::MIN, i32::MAX]);
+        Ok(())
+    }
+}
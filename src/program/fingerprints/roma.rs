@@ -0,0 +1,55 @@
+use anyhow::Result;
+
+use super::{Context, Fingerprint, FungeInteger};
+
+/// `ROMA`: push the value of a roman numeral digit.
+pub(super) struct Roma;
+
+impl<T: FungeInteger> Fingerprint<T> for Roma {
+    fn name(&self) -> &'static str {
+        "ROMA"
+    }
+
+    fn instructions(&self) -> &'static str {
+        "CDILMVX"
+    }
+
+    fn execute(&self, instruction: char, funge: &mut dyn Context<T>) -> Result<()> {
+        let value = match instruction {
+            'C' => 100,
+            'D' => 500,
+            'I' => 1,
+            'L' => 50,
+            'M' => 1000,
+            'V' => 5,
+            'X' => 10,
+            _ => unreachable!("ROMA does not provide {}", instruction),
+        };
+        funge.push(T::from(value).unwrap_or_default());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::program::fingerprints::execute_on;
+    use anyhow::ensure;
+
+    #[test]
+    fn test_digits() -> Result<()> {
+        for (instruction, value) in [
+            ('I', 1),
+            ('V', 5),
+            ('X', 10),
+            ('L', 50),
+            ('C', 100),
+            ('D', 500),
+            ('M', 1000),
+        ] {
+            ensure!(execute_on(&Roma, instruction, &[])? == (vec![value], false));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,274 @@
+use anyhow::Result;
+
+use super::{Context, Fingerprint, FungeInteger};
+
+/**
+`STRN`: string manipulation on `0"gnirts`.
+
+"Upper" strings are the ones closer to the top of the TOSS i.e., the ones that are popped first.
+*/
+pub(super) struct Strn;
+
+impl<T: FungeInteger> Fingerprint<T> for Strn {
+    fn name(&self) -> &'static str {
+        "STRN"
+    }
+
+    fn instructions(&self) -> &'static str {
+        "ACDFGILMNPRSV"
+    }
+
+    fn execute(&self, instruction: char, funge: &mut dyn Context<T>) -> Result<()> {
+        match instruction {
+            // Append the bottom string to the upper string
+            'A' => {
+                let (upper, bottom) = (funge.pop_gnirts(), funge.pop_gnirts());
+                funge.push_gnirts(&(upper + &bottom));
+            }
+            // Compare the strings: push -1, 0 or 1 if the upper string is less than, equal to or greater than the bottom one
+            'C' => {
+                let (upper, bottom) = (funge.pop_gnirts(), funge.pop_gnirts());
+                let ordering = upper.cmp(&bottom) as i8;
+                funge.push(T::from(ordering).unwrap_or_default());
+            }
+            // Display a string
+            'D' => {
+                let s = funge.pop_gnirts();
+                funge.print(&s)?;
+            }
+            // Search for the bottom string in the upper string and push the rest of the upper string
+            // from the first occurrence onwards, or an empty string if it is not found
+            'F' => {
+                let (upper, bottom) = (funge.pop_gnirts(), funge.pop_gnirts());
+                let found = upper.find(&bottom).map_or("", |i| &upper[i..]);
+                funge.push_gnirts(found);
+            }
+            // Get a (null terminated) string from a position of Funge-Space, relative to the storage offset
+            'G' => {
+                let position = funge.pop_vector();
+                let (mut x, y, z) = funge.cursor().translate_to_storage_position(position);
+                let mut s = String::new();
+                loop {
+                    // a string that is not null terminated ends at the edge of Funge-Space
+                    if funge.out_of_bounds((x.clone(), y.clone(), z.clone())) {
+                        break;
+                    }
+                    let c = funge.get_cell((x.clone(), y.clone(), z.clone()));
+                    if c == T::zero() {
+                        break;
+                    }
+                    s.push(to_char(c));
+                    x += T::one();
+                }
+                funge.push_gnirts(&s);
+            }
+            // Input a string (a line, without its EOL)
+            'I' => {
+                let line = funge.read_line()?;
+                funge.push_gnirts(line.trim_end_matches(&['\r', '\n'][..]));
+            }
+            // Leftmost/rightmost n characters of a string
+            'L' | 'R' => {
+                let n = funge.pop();
+                let s = funge.pop_gnirts();
+                match n.to_usize() {
+                    Some(n) => {
+                        let n = n.min(s.chars().count());
+                        let substring: String = if instruction == 'L' {
+                            s.chars().take(n).collect()
+                        } else {
+                            s.chars().skip(s.chars().count() - n).collect()
+                        };
+                        funge.push_gnirts(&substring);
+                    }
+                    None => funge.reflect(),
+                }
+            }
+            // n characters of a string, starting at position start
+            'M' => {
+                let (n, start) = (funge.pop(), funge.pop());
+                let s = funge.pop_gnirts();
+                match (start.to_usize(), n.to_usize()) {
+                    (Some(start), Some(n)) if start <= s.chars().count() => {
+                        let substring: String = s.chars().skip(start).take(n).collect();
+                        funge.push_gnirts(&substring);
+                    }
+                    _ => funge.reflect(),
+                }
+            }
+            // Length of a string: the string is left on the stack
+            'N' => {
+                let s = funge.pop_gnirts();
+                funge.push_gnirts(&s);
+                funge.push(T::from(s.chars().count()).unwrap_or_default());
+            }
+            // Put a string (and its null terminator) at a position of Funge-Space, relative to the storage offset
+            'P' => {
+                let position = funge.pop_vector();
+                let s = funge.pop_gnirts();
+                let (mut x, y, z) = funge.cursor().translate_to_storage_position(position);
+                for c in s.chars().map(|c| T::from(c as u32).unwrap_or_default()) {
+                    funge.put_cell((x.clone(), y.clone(), z.clone()), c);
+                    x += T::one();
+                }
+                funge.put_cell((x, y, z), T::zero());
+            }
+            // String representation of a number
+            'S' => {
+                let n = funge.pop();
+                funge.push_gnirts(&n.to_string());
+            }
+            // Value of a string i.e., the number it starts with (or 0)
+            'V' => {
+                let s = funge.pop_gnirts();
+                funge.push(parse_number(&s));
+            }
+            _ => unreachable!("STRN does not provide {}", instruction),
+        }
+        Ok(())
+    }
+}
+
+fn to_char<T: FungeInteger>(c: T) -> char {
+    char::from_u32(c.to_u32().unwrap_or_default()).unwrap_or_default()
+}
+
+/**
+Parses the number at the start of `s` (after any leading whitespace)
+the way C's `atoi` does i.e., ignoring whatever follows it; returns 0 if there is no number.
+A number that does not fit in a cell wraps around, like the arithmetic instructions do.
+*/
+fn parse_number<T: FungeInteger>(s: &str) -> T {
+    let s = s.trim_start();
+    let (negative, digits) = match s.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, s.strip_prefix('+').unwrap_or(s)),
+    };
+    let mut n = T::zero();
+    let ten = T::from(10).unwrap_or_default();
+    for d in digits.chars().map_while(|c| c.to_digit(10)) {
+        n = n
+            .overflowing_mul(&ten)
+            .0
+            .overflowing_add(&T::from(d).unwrap_or_default())
+            .0;
+    }
+    if negative {
+        T::zero().overflowing_sub(&n).0
+    } else {
+        n
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::program::{fingerprints::execute_on, Program};
+    use anyhow::ensure;
+
+    /// Returns `s` as a `0"gnirts` on the stack (from bottom to top).
+    fn gnirts(s: &str) -> Vec<i32> {
+        let mut stack = vec![0];
+        stack.extend(s.bytes().rev().map(Into::<i32>::into));
+        stack
+    }
+
+    #[test]
+    fn test_append() -> Result<()> {
+        let stack = [gnirts("world"), gnirts("hello ")].concat();
+        ensure!(execute_on(&Strn, 'A', &stack)? == (gnirts("hello world"), false));
+        Ok(())
+    }
+
+    #[test]
+    fn test_compare() -> Result<()> {
+        for (upper, bottom, expected) in [("abc", "abd", -1), ("abc", "abc", 0), ("b", "abc", 1)] {
+            let stack = [gnirts(bottom), gnirts(upper)].concat();
+            ensure!(execute_on(&Strn, 'C', &stack)? == (vec![expected], false));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_search() -> Result<()> {
+        let stack = [gnirts("lo"), gnirts("hello world")].concat();
+        ensure!(execute_on(&Strn, 'F', &stack)? == (gnirts("lo world"), false));
+        let stack = [gnirts("xyz"), gnirts("hello world")].concat();
+        ensure!(execute_on(&Strn, 'F', &stack)? == (gnirts(""), false));
+        Ok(())
+    }
+
+    #[test]
+    fn test_left_right() -> Result<()> {
+        let stack = [gnirts("hello"), vec![2]].concat();
+        ensure!(execute_on(&Strn, 'L', &stack)? == (gnirts("he"), false));
+        ensure!(execute_on(&Strn, 'R', &stack)? == (gnirts("lo"), false));
+        let stack = [gnirts("hello"), vec![10]].concat();
+        ensure!(execute_on(&Strn, 'R', &stack)? == (gnirts("hello"), false));
+        let stack = [gnirts("hello"), vec![i32::MAX]].concat();
+        ensure!(execute_on(&Strn, 'L', &stack)? == (gnirts("hello"), false));
+        ensure!(execute_on(&Strn, 'R', &stack)? == (gnirts("hello"), false));
+        let stack = [gnirts("hello"), vec![i32::MIN]].concat();
+        ensure!(execute_on(&Strn, 'L', &stack)? == (vec![], true));
+        ensure!(execute_on(&Strn, 'R', &stack)? == (vec![], true));
+        Ok(())
+    }
+
+    #[test]
+    fn test_mid() -> Result<()> {
+        let stack = [gnirts("hello"), vec![1, 3]].concat();
+        ensure!(execute_on(&Strn, 'M', &stack)? == (gnirts("ell"), false));
+        let stack = [gnirts("hello"), vec![2, i32::MAX]].concat();
+        ensure!(execute_on(&Strn, 'M', &stack)? == (gnirts("llo"), false));
+        let stack = [gnirts("hello"), vec![5, 1]].concat();
+        ensure!(execute_on(&Strn, 'M', &stack)? == (gnirts(""), false));
+        for (start, n) in [(6, 1), (i32::MAX, 1), (-1, 1), (1, -1)] {
+            let stack = [gnirts("hello"), vec![start, n]].concat();
+            ensure!(execute_on(&Strn, 'M', &stack)? == (vec![], true));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_length() -> Result<()> {
+        let expected = [gnirts("hello"), vec![5]].concat();
+        ensure!(execute_on(&Strn, 'N', &gnirts("hello"))? == (expected, false));
+        Ok(())
+    }
+
+    #[test]
+    fn test_number_conversions() -> Result<()> {
+        ensure!(execute_on(&Strn, 'S', &[-123])? == (gnirts("-123"), false));
+        ensure!(execute_on(&Strn, 'V', &gnirts("  -123abc"))? == (vec![-123], false));
+        ensure!(execute_on(&Strn, 'V', &gnirts("abc"))? == (vec![0], false));
+        ensure!(execute_on(&Strn, 'S', &[i32::MIN])? == (gnirts("-2147483648"), false));
+        Ok(())
+    }
+
+    #[test]
+    fn test_number_overflow() -> Result<()> {
+        ensure!(execute_on(&Strn, 'V', &gnirts("-2147483648"))? == (vec![i32::MIN], false));
+        ensure!(execute_on(&Strn, 'V', &gnirts("2147483648"))? == (vec![i32::MIN], false));
+        ensure!(execute_on(&Strn, 'V', &gnirts("4294967297"))? == (vec![1], false));
+        Ok(())
+    }
+
+    #[test]
+    fn test_put_get() -> Result<()> {
+        let mut program = Program::<i32>::from(vec![b"@".to_vec()]);
+        program.ip_mut().cursor.set_storage_offset((1, 1, 0));
+        for x in [gnirts("hi"), vec![2, 3]].concat() {
+            program.push(x);
+        }
+        Strn.execute('P', &mut program)?;
+        // the string is put relative to the storage offset, null terminator included
+        ensure!(program.get_cell((3, 4, 0)) == 'h' as i32);
+        ensure!(program.get_cell((4, 4, 0)) == 'i' as i32);
+        ensure!(program.get_cell((5, 4, 0)) == 0);
+
+        program.push_vector((2, 3, 0));
+        Strn.execute('G', &mut program)?;
+        ensure!(program.ip().sstack.get_stacks()[0] == gnirts("hi"));
+        Ok(())
+    }
+}
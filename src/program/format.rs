@@ -0,0 +1,53 @@
+use super::fungetypes::FungeInteger;
+
+/// Returns the components of `vector` that a program of `dimensions` dimensions uses.
+fn components<T: FungeInteger>((x, y, z): &(T, T, T), dimensions: usize) -> Vec<String> {
+    [x, y, z]
+        .iter()
+        .take(dimensions)
+        .map(|c| c.to_string())
+        .collect()
+}
+
+/// Formats a vector, with one component per dimension, like `(1, -2)`.
+pub fn format_vector<T: FungeInteger>(vector: &(T, T, T), dimensions: usize) -> String {
+    format!("({})", components(vector, dimensions).join(", "))
+}
+
+/// Formats a vector as a JSON array, with one number per dimension, like `[1,-2]`.
+pub(super) fn json_vector<T: FungeInteger>(vector: &(T, T, T), dimensions: usize) -> String {
+    format!("[{}]", components(vector, dimensions).join(","))
+}
+
+/// Formats a value as a number, followed by the (printable) character it stands for, like `65 'A'`.
+pub fn format_value<T: FungeInteger>(value: &T) -> String {
+    match value.to_u8() {
+        Some(b) if (b' '..=b'~').contains(&b) => format!("{} '{}'", value, b as char),
+        _ => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_vector() {
+        assert_eq!(format_vector(&(1, -2, 3), 1), "(1)");
+        assert_eq!(format_vector(&(1, -2, 3), 3), "(1, -2, 3)");
+    }
+
+    #[test]
+    fn test_json_vector() {
+        assert_eq!(json_vector(&(1, -2, 3), 1), "[1]");
+        assert_eq!(json_vector(&(1, -2, 3), 2), "[1,-2]");
+        assert_eq!(json_vector(&(1, -2, 3), 3), "[1,-2,3]");
+    }
+
+    #[test]
+    fn test_format_value() {
+        assert_eq!(format_value(&65), "65 'A'");
+        assert_eq!(format_value(&10), "10");
+        assert_eq!(format_value(&-65), "-65");
+    }
+}
@@ -1,32 +1,166 @@
+use num_bigint::BigInt;
+use num_traits::{FromPrimitive, NumCast, ToPrimitive};
+
+/**
+The type of the cells of Funge-Space and of the values on the stacks.
+
+It is implemented by the signed primitive integers (`i32`, `i64` and `i128`),
+which wrap around on overflow, and by `BigInt`, which never overflows.
+Since the latter lives on the heap, cells are `Clone` but not `Copy`.
+*/
 pub trait FungeInteger:
-    num_traits::PrimInt
+    num_traits::Num
     + num_traits::Signed
-    + num_traits::ops::overflowing::OverflowingAdd
-    + num_traits::ops::overflowing::OverflowingMul
-    + num_traits::ops::overflowing::OverflowingSub
     + num_traits::ToPrimitive
+    + num_traits::CheckedMul
+    + Clone
+    + Ord
+    + std::hash::Hash
     + Default
     + std::ops::AddAssign
     + std::ops::SubAssign
     + std::ops::MulAssign
+    + std::ops::BitAnd<Output = Self>
+    + std::ops::BitOr<Output = Self>
+    + std::ops::BitXor<Output = Self>
+    + std::ops::Not<Output = Self>
     + std::fmt::Display
     + std::fmt::Debug
 {
+    /// The number of bytes per cell, as reported by `y` (item 2); 0 means unbounded.
+    const SIZE: usize;
+
+    /// Converts `n` to a cell, if it fits in one.
+    fn from<N: ToPrimitive>(n: N) -> Option<Self>;
+    /// The greatest value a cell can hold, if there is one.
+    fn max_value() -> Option<Self>;
+    /// The least value a cell can hold, if there is one.
+    fn min_value() -> Option<Self>;
+    /// Returns `self + rhs` and whether the addition wrapped around.
+    fn overflowing_add(&self, rhs: &Self) -> (Self, bool);
+    /// Returns `self - rhs` and whether the subtraction wrapped around.
+    fn overflowing_sub(&self, rhs: &Self) -> (Self, bool);
+    /// Returns `self * rhs` and whether the multiplication wrapped around.
+    fn overflowing_mul(&self, rhs: &Self) -> (Self, bool);
+    /// Returns `self / rhs` and whether the division wrapped around (only the least cell divided by -1 does).
+    fn overflowing_div(&self, rhs: &Self) -> (Self, bool);
+    /// Returns `self % rhs` and whether the division it comes from wrapped around; the remainder itself is 0 then.
+    fn overflowing_rem(&self, rhs: &Self) -> (Self, bool);
 }
 
-impl<
-        T: num_traits::PrimInt
-            + num_traits::Signed
-            + num_traits::ops::overflowing::OverflowingAdd
-            + num_traits::ops::overflowing::OverflowingMul
-            + num_traits::ops::overflowing::OverflowingSub
-            + num_traits::ToPrimitive
-            + Default
-            + std::ops::AddAssign
-            + std::ops::SubAssign
-            + std::ops::MulAssign
-            + std::fmt::Display
-            + std::fmt::Debug,
-    > FungeInteger for T
-{
+macro_rules! impl_funge_integer {
+    ($($t:ty),*) => {$(
+        impl FungeInteger for $t {
+            const SIZE: usize = std::mem::size_of::<$t>();
+
+            fn from<N: ToPrimitive>(n: N) -> Option<Self> {
+                <$t as NumCast>::from(n)
+            }
+
+            fn max_value() -> Option<Self> {
+                Some(<$t>::MAX)
+            }
+
+            fn min_value() -> Option<Self> {
+                Some(<$t>::MIN)
+            }
+
+            fn overflowing_add(&self, rhs: &Self) -> (Self, bool) {
+                <$t>::overflowing_add(*self, *rhs)
+            }
+
+            fn overflowing_sub(&self, rhs: &Self) -> (Self, bool) {
+                <$t>::overflowing_sub(*self, *rhs)
+            }
+
+            fn overflowing_mul(&self, rhs: &Self) -> (Self, bool) {
+                <$t>::overflowing_mul(*self, *rhs)
+            }
+
+            fn overflowing_div(&self, rhs: &Self) -> (Self, bool) {
+                <$t>::overflowing_div(*self, *rhs)
+            }
+
+            fn overflowing_rem(&self, rhs: &Self) -> (Self, bool) {
+                <$t>::overflowing_rem(*self, *rhs)
+            }
+        }
+    )*};
+}
+
+impl_funge_integer!(i32, i64, i128);
+
+impl FungeInteger for BigInt {
+    const SIZE: usize = 0;
+
+    fn from<N: ToPrimitive>(n: N) -> Option<Self> {
+        // floats do not necessarily fit in an i128, but they do fit in a BigInt
+        n.to_i128()
+            .and_then(BigInt::from_i128)
+            .or_else(|| n.to_f64().and_then(BigInt::from_f64))
+    }
+
+    fn max_value() -> Option<Self> {
+        None
+    }
+
+    fn min_value() -> Option<Self> {
+        None
+    }
+
+    fn overflowing_add(&self, rhs: &Self) -> (Self, bool) {
+        (self + rhs, false)
+    }
+
+    fn overflowing_sub(&self, rhs: &Self) -> (Self, bool) {
+        (self - rhs, false)
+    }
+
+    fn overflowing_mul(&self, rhs: &Self) -> (Self, bool) {
+        (self * rhs, false)
+    }
+
+    fn overflowing_div(&self, rhs: &Self) -> (Self, bool) {
+        (self / rhs, false)
+    }
+
+    fn overflowing_rem(&self, rhs: &Self) -> (Self, bool) {
+        (self % rhs, false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_traits::One;
+
+    #[test]
+    fn test_primitive_cells_overflow() {
+        assert_eq!(
+            FungeInteger::overflowing_add(&i32::MAX, &1),
+            (i32::MIN, true)
+        );
+        assert_eq!(
+            FungeInteger::overflowing_mul(&i64::MAX, &1),
+            (i64::MAX, false)
+        );
+        assert_eq!(
+            FungeInteger::overflowing_div(&i32::MIN, &-1),
+            (i32::MIN, true)
+        );
+        assert_eq!(FungeInteger::overflowing_rem(&i64::MIN, &-1), (0, true));
+        assert_eq!(<i128 as FungeInteger>::from(1e40), None);
+    }
+
+    #[test]
+    fn test_bignum_cells_do_not_overflow() {
+        let max = BigInt::from_i32(i32::MAX).unwrap();
+        let (sum, overflowed) = max.overflowing_add(&BigInt::one());
+        assert!(!overflowed);
+        assert_eq!(sum.to_i64(), Some(i32::MAX as i64 + 1));
+        assert_eq!(
+            <BigInt as FungeInteger>::from(1e40).unwrap().to_f64(),
+            Some(1e40)
+        );
+    }
 }
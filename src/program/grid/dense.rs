@@ -0,0 +1,291 @@
+use super::super::{bounds::Bounds, fungetypes::FungeInteger};
+use std::ops::{Index, IndexMut};
+
+/**
+Funge-Space, stored densely: a box of cells, stored as planes of rows of cells, that grows
+to fit every non-space cell and shrinks back when cells at its edges are erased.
+
+Befunge programs simply occupy a single plane.
+*/
+#[derive(Default)]
+pub(super) struct DenseGrid<T: FungeInteger> {
+    grid: Vec<Vec<Vec<T>>>,
+    bounds: Bounds<T>,
+    /// The number of times the grid grew, in any direction
+    growths: u64,
+    /// The number of times the grid shrank, in any direction
+    shrinks: u64,
+}
+
+impl<T: FungeInteger> From<(Vec<Vec<Vec<T>>>, Bounds<T>)> for DenseGrid<T> {
+    fn from((grid, bounds): (Vec<Vec<Vec<T>>>, Bounds<T>)) -> Self {
+        Self {
+            grid,
+            bounds,
+            growths: 0,
+            shrinks: 0,
+        }
+    }
+}
+
+impl<T: FungeInteger> Index<(T, T, T)> for DenseGrid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y, z): (T, T, T)) -> &Self::Output {
+        // if this panics, something is broken in the logic:
+        // Execution should NEVER reach here if the position is out of bounds
+        let (i, j, k) = self.offsets(x, y, z);
+        &self.grid[k][j][i]
+    }
+}
+
+// Resizes Funge-Space if necessary
+impl<T: FungeInteger> IndexMut<(T, T, T)> for DenseGrid<T> {
+    fn index_mut(&mut self, (x, y, z): (T, T, T)) -> &mut Self::Output {
+        self.expand_z(z.clone());
+        self.expand_y(y.clone());
+        self.expand_x(x.clone());
+        let (i, j, k) = self.offsets(x, y, z);
+        &mut self.grid[k][j][i]
+    }
+}
+
+/// Returns `n` as a `usize`, for the (non-negative) offsets and lengths of the grid.
+fn to_usize<T: FungeInteger>(n: T) -> usize {
+    n.to_usize().unwrap_or_default()
+}
+
+/// Returns the number of cells from `lower` to `upper` (both inclusive); 0 if `upper` lies before `lower`.
+fn length<T: FungeInteger>(lower: T, upper: T) -> usize {
+    (upper - lower).to_usize().map_or(0, |n| n + 1)
+}
+
+impl<T: FungeInteger> DenseGrid<T> {
+    pub fn get_bounds(&self) -> &Bounds<T> {
+        &self.bounds
+    }
+
+    /// Returns the number of times the grid grew and the number of times it shrank.
+    pub fn resizes(&self) -> (u64, u64) {
+        (self.growths, self.shrinks)
+    }
+
+    /// Wraps the `out_of_bounds` method of the `bounds` struct.
+    pub fn out_of_bounds(&self, position: (T, T, T)) -> bool {
+        self.bounds.out_of_bounds(position)
+    }
+
+    /// Returns the indices of the cell on `(x, y, z)` in the underlying vectors.
+    fn offsets(&self, x: T, y: T, z: T) -> (usize, usize, usize) {
+        (
+            to_usize(x - self.bounds.lower_x()),
+            to_usize(y - self.bounds.lower_y()),
+            to_usize(z - self.bounds.lower_z()),
+        )
+    }
+
+    fn space() -> T {
+        T::from(32).unwrap()
+    }
+
+    fn width(&self) -> usize {
+        length(self.bounds.lower_x(), self.bounds.upper_x())
+    }
+
+    fn height(&self) -> usize {
+        length(self.bounds.lower_y(), self.bounds.upper_y())
+    }
+
+    /// Adds (empty) planes, so that plane `z` exists.
+    fn expand_z(&mut self, z: T) {
+        let plane = vec![vec![Self::space(); self.width()]; self.height()];
+        if z < self.bounds.lower_z() {
+            // need to resize depth to the negative
+            let n = to_usize(self.bounds.lower_z() - z.clone());
+            self.grid.splice(0..0, vec![plane; n]);
+            self.bounds.set_lower_z(z);
+            self.growths += 1;
+        } else if z > self.bounds.upper_z() {
+            // need to resize depth to the positive
+            let n = to_usize(z.clone() - self.bounds.upper_z());
+            self.grid.append(&mut vec![plane; n]);
+            self.bounds.set_upper_z(z);
+            self.growths += 1;
+        }
+    }
+
+    /// Adds (empty) rows to all planes, so that row `y` exists.
+    fn expand_y(&mut self, y: T) {
+        let row = vec![Self::space(); self.width()];
+        if y < self.bounds.lower_y() {
+            // need to resize height OF ALL PLANES to the negative
+            let n = to_usize(self.bounds.lower_y() - y.clone());
+            for plane in self.grid.iter_mut() {
+                plane.splice(0..0, vec![row.clone(); n]);
+            }
+            self.bounds.set_lower_y(y);
+            self.growths += 1;
+        } else if y > self.bounds.upper_y() {
+            // need to resize height OF ALL PLANES to the positive
+            let n = to_usize(y.clone() - self.bounds.upper_y());
+            for plane in self.grid.iter_mut() {
+                plane.append(&mut vec![row.clone(); n]);
+            }
+            self.bounds.set_upper_y(y);
+            self.growths += 1;
+        }
+    }
+
+    /// Adds (empty) cells to all rows, so that column `x` exists.
+    fn expand_x(&mut self, x: T) {
+        if x < self.bounds.lower_x() {
+            // need to resize width OF ALL ROWS to the negative
+            let n = to_usize(self.bounds.lower_x() - x.clone());
+            for row in self.grid.iter_mut().flatten() {
+                row.splice(0..0, vec![Self::space(); n]);
+            }
+            self.bounds.set_lower_x(x);
+            self.growths += 1;
+        } else if x > self.bounds.upper_x() {
+            // need to resize width OF ALL ROWS to the positive
+            let width = to_usize(x.clone() - self.bounds.lower_x()) + 1;
+            for row in self.grid.iter_mut().flatten() {
+                row.resize(width, Self::space());
+            }
+            self.bounds.set_upper_x(x);
+            self.growths += 1;
+        }
+    }
+
+    /// Returns the positions and the values of all the non-space cells.
+    pub fn non_space_cells(&self) -> Vec<((T, T, T), T)> {
+        let offset = |n: usize, lower: T| T::from(n).unwrap_or_default() + lower;
+        let mut cells = Vec::new();
+        for (k, plane) in self.grid.iter().enumerate() {
+            for (j, row) in plane.iter().enumerate() {
+                for (i, c) in row.iter().enumerate() {
+                    if *c != Self::space() {
+                        let position = (
+                            offset(i, self.bounds.lower_x()),
+                            offset(j, self.bounds.lower_y()),
+                            offset(k, self.bounds.lower_z()),
+                        );
+                        cells.push((position, c.clone()));
+                    }
+                }
+            }
+        }
+        cells
+    }
+
+    /**
+    Checks if a shrinking of the grid space is in order and,
+    if it is, it performs it.
+
+    This method should only be called after a `p` instruction that puts a space;
+    otherwise, it does nothing and just wastes execution time in redundant checks.
+    The grid never shrinks to less than a single cell.
+    */
+    pub fn shrink(&mut self, (x, y, z): (T, T, T)) {
+        let size = (self.grid.len(), self.height(), self.width());
+        let space = Self::space();
+        let is_empty_row = |row: &Vec<T>| row.iter().all(|c| *c == space);
+
+        /* should the planes be shrunk? */
+        if z == self.bounds.upper_z() {
+            // we MAY need to shrink Z from the positive
+            while self.grid.len() > 1 && self.grid.last().unwrap().iter().all(is_empty_row) {
+                self.grid.pop();
+                self.bounds.set_upper_z(self.bounds.upper_z() - T::one());
+            }
+        } else if z == self.bounds.lower_z() {
+            // we MAY need to shrink Z from the negative
+            while self.grid.len() > 1 && self.grid[0].iter().all(is_empty_row) {
+                self.grid.remove(0);
+                self.bounds.set_lower_z(self.bounds.lower_z() + T::one());
+            }
+        }
+
+        /* should the rows be shrunk? */
+        if y == self.bounds.upper_y() {
+            // we MAY need to shrink Y from the positive
+            while self.height() > 1
+                && self
+                    .grid
+                    .iter()
+                    .all(|plane| is_empty_row(&plane[plane.len() - 1]))
+            {
+                for plane in self.grid.iter_mut() {
+                    plane.pop();
+                }
+                self.bounds.set_upper_y(self.bounds.upper_y() - T::one());
+            }
+        } else if y == self.bounds.lower_y() {
+            // we MAY need to shrink Y from the negative
+            while self.height() > 1 && self.grid.iter().all(|plane| is_empty_row(&plane[0])) {
+                for plane in self.grid.iter_mut() {
+                    plane.remove(0);
+                }
+                self.bounds.set_lower_y(self.bounds.lower_y() + T::one());
+            }
+        }
+
+        /* should the columns be shrunk? */
+        if x == self.bounds.upper_x() {
+            // we MAY need to shrink X from the positive
+            while self.width() > 1
+                && self
+                    .grid
+                    .iter()
+                    .flatten()
+                    .all(|row| row[row.len() - 1] == space)
+            {
+                for row in self.grid.iter_mut().flatten() {
+                    row.pop();
+                }
+                self.bounds.set_upper_x(self.bounds.upper_x() - T::one());
+            }
+        } else if x == self.bounds.lower_x() {
+            // we MAY need to shrink X from the negative
+            while self.width() > 1 && self.grid.iter().flatten().all(|row| row[0] == space) {
+                for row in self.grid.iter_mut().flatten() {
+                    row.remove(0);
+                }
+                self.bounds.set_lower_x(self.bounds.lower_x() + T::one());
+            }
+        }
+
+        if (self.grid.len(), self.height(), self.width()) != size {
+            self.shrinks += 1;
+        }
+    }
+
+    #[cfg(test)]
+    /// Returns the widths of all the rows of all the planes of the program
+    pub fn row_widths(&self) -> Vec<usize> {
+        self.grid.iter().flatten().map(|row| row.len()).collect()
+    }
+}
+
+impl<T: FungeInteger> std::fmt::Debug for DenseGrid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for (k, plane) in self.grid.iter().enumerate() {
+            if k > 0 {
+                // planes are separated by form feeds, like in Trefunge source files
+                writeln!(f, "\x0c")?;
+            }
+            for row in plane {
+                writeln!(
+                    f,
+                    "{}",
+                    row.iter()
+                        .map(|b| {
+                            char::from_u32(b.to_u32().unwrap_or_default()).unwrap_or_default()
+                        })
+                        .collect::<String>()
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
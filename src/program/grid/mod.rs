@@ -0,0 +1,349 @@
+use super::{bounds::Bounds, fungetypes::FungeInteger, Storage};
+use dense::DenseGrid;
+use occupancy::Occupancy;
+use sparse::SparseGrid;
+
+mod dense;
+mod occupancy;
+mod sparse;
+
+/**
+The number of cells beyond which a Funge-Space that is stored automatically
+stops being stored densely; 4M cells take 16MB, for 32-bit cells.
+*/
+const DENSE_LIMIT: u128 = 1 << 22;
+
+enum Space<T: FungeInteger> {
+    Dense(DenseGrid<T>),
+    Sparse(SparseGrid<T>),
+}
+
+/**
+Funge-Space: the cells of a program, stored either densely or sparsely (see `Storage`),
+along with its bounds, which grow to fit every non-space cell and shrink back
+when cells at their edges are erased.
+*/
+pub(super) struct Grid<T: FungeInteger> {
+    storage: Storage,
+    space: Space<T>,
+    occupancy: Occupancy<T>,
+}
+
+impl<T: FungeInteger> Default for Grid<T> {
+    fn default() -> Self {
+        Self {
+            storage: Storage::default(),
+            space: Space::Dense(DenseGrid::default()),
+            occupancy: Occupancy::default(),
+        }
+    }
+}
+
+impl<T: FungeInteger> From<(Vec<Vec<Vec<T>>>, Bounds<T>)> for Grid<T> {
+    fn from(source: (Vec<Vec<Vec<T>>>, Bounds<T>)) -> Self {
+        let dense = DenseGrid::from(source);
+        let mut occupancy = Occupancy::default();
+        for (position, _) in dense.non_space_cells() {
+            occupancy.add(position);
+        }
+        Self {
+            storage: Storage::default(),
+            space: Space::Dense(dense),
+            occupancy,
+        }
+    }
+}
+
+impl<T: FungeInteger> Grid<T> {
+    /**
+    Sets the way Funge-Space is stored from now on, moving its cells if necessary;
+    a Funge-Space that is already stored sparsely stays that way, though.
+    */
+    pub fn set_storage(&mut self, storage: Storage) {
+        self.storage = storage;
+        if let (Space::Dense(dense), Storage::Sparse) = (&self.space, storage) {
+            self.space = Space::Sparse(SparseGrid::from(dense));
+        }
+    }
+
+    pub fn get_bounds(&self) -> &Bounds<T> {
+        match &self.space {
+            Space::Dense(dense) => dense.get_bounds(),
+            Space::Sparse(sparse) => sparse.get_bounds(),
+        }
+    }
+
+    /// Returns the number of times Funge-Space grew and the number of times it shrank.
+    pub fn resizes(&self) -> (u64, u64) {
+        match &self.space {
+            Space::Dense(dense) => dense.resizes(),
+            Space::Sparse(sparse) => sparse.resizes(),
+        }
+    }
+
+    /// Wraps the `out_of_bounds` method of the `bounds` struct.
+    pub fn out_of_bounds(&self, position: (T, T, T)) -> bool {
+        self.get_bounds().out_of_bounds(position)
+    }
+
+    /**
+    Returns the value of the cell on `position`.
+
+    The position must lie within the bounds of Funge-Space, which is not checked
+    for a dense Funge-Space; the cells out of the bounds are spaces, anyway.
+    */
+    pub fn get(&self, position: (T, T, T)) -> T {
+        match &self.space {
+            Space::Dense(dense) => dense[position].clone(),
+            Space::Sparse(sparse) => sparse.get(position),
+        }
+    }
+
+    /// Sets the value of the cell on `position`, growing Funge-Space if necessary.
+    pub fn set(&mut self, position: (T, T, T), c: T) {
+        let space = T::from(32).unwrap();
+        let previous = if self.out_of_bounds(position.clone()) {
+            space.clone()
+        } else {
+            self.get(position.clone())
+        };
+        if previous == space && c != space {
+            self.occupancy.add(position.clone());
+        } else if previous != space && c == space {
+            self.occupancy.remove(position.clone());
+        }
+        if let Space::Dense(dense) = &self.space {
+            if self.storage == Storage::Auto
+                && dense.out_of_bounds(position.clone())
+                && volume(dense.get_bounds(), &position) > DENSE_LIMIT
+            {
+                self.space = Space::Sparse(SparseGrid::from(dense));
+            }
+        }
+        match &mut self.space {
+            Space::Dense(dense) => dense[position] = c,
+            Space::Sparse(sparse) => sparse.set(position, c),
+        }
+    }
+
+    /**
+    Returns the least point which contains a non-space cell, relative to the origin
+    (or the least point of the bounds, if all cells are spaces).
+    */
+    pub fn get_least_point(&self) -> (T, T, T) {
+        match self.occupancy.non_space_box() {
+            Some([least, _]) => least,
+            None => {
+                let bounds = self.get_bounds();
+                (bounds.lower_x(), bounds.lower_y(), bounds.lower_z())
+            }
+        }
+    }
+
+    /// Returns the greatest point which contains a non-space cell, relative to the least point.
+    pub fn get_greatest_point(&self) -> (T, T, T) {
+        match self.occupancy.non_space_box() {
+            // wrapping around, like the arithmetic instructions do, if Funge-Space spans more than a cell
+            Some([least, greatest]) => (
+                greatest.0.overflowing_sub(&least.0).0,
+                greatest.1.overflowing_sub(&least.1).0,
+                greatest.2.overflowing_sub(&least.2).0,
+            ),
+            None => (T::zero(), T::zero(), T::zero()),
+        }
+    }
+
+    /**
+    Checks if a shrinking of Funge-Space is in order and, if it is, it performs it.
+
+    This method should only be called after a `p` instruction that puts a space;
+    otherwise, it does nothing and just wastes execution time in redundant checks.
+    */
+    pub fn shrink(&mut self, position: (T, T, T)) {
+        match &mut self.space {
+            Space::Dense(dense) => dense.shrink(position),
+            Space::Sparse(sparse) => sparse.shrink(position, self.occupancy.non_space_box()),
+        }
+    }
+
+    #[cfg(test)]
+    /// Returns whether Funge-Space is currently stored sparsely
+    pub fn is_sparse(&self) -> bool {
+        matches!(self.space, Space::Sparse(_))
+    }
+
+    #[cfg(test)]
+    /// Returns the widths of all the rows of all the planes of a dense Funge-Space
+    pub fn row_widths(&self) -> Vec<usize> {
+        match &self.space {
+            Space::Dense(dense) => dense.row_widths(),
+            Space::Sparse(_) => vec![],
+        }
+    }
+}
+
+/**
+Returns the number of cells of the smallest box that contains both `bounds` and `position`;
+`u128::MAX` if the box spans more cells than that or than a cell can count.
+*/
+fn volume<T: FungeInteger>(bounds: &Bounds<T>, (x, y, z): &(T, T, T)) -> u128 {
+    let length = |lower: T, upper: T, c: &T| {
+        let lower = lower.min(c.clone());
+        let upper = upper.max(c.clone());
+        match upper.overflowing_sub(&lower) {
+            (extent, false) => extent.to_u128().map_or(u128::MAX, |n| n.saturating_add(1)),
+            (_, true) => u128::MAX,
+        }
+    };
+    length(bounds.lower_x(), bounds.upper_x(), x)
+        .saturating_mul(length(bounds.lower_y(), bounds.upper_y(), y))
+        .saturating_mul(length(bounds.lower_z(), bounds.upper_z(), z))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_grid(storage: Storage) -> Grid<i64> {
+        let source = vec![vec![b"v @".iter().map(|&b| b as i64).collect()]];
+        let mut grid = Grid::from((source, Bounds::new((0, 0, 0), (2, 0, 0))));
+        grid.set_storage(storage);
+        grid
+    }
+
+    fn bounds(grid: &Grid<i64>) -> ((i64, i64, i64), (i64, i64, i64)) {
+        let bounds = grid.get_bounds();
+        (
+            (bounds.lower_x(), bounds.lower_y(), bounds.lower_z()),
+            (bounds.upper_x(), bounds.upper_y(), bounds.upper_z()),
+        )
+    }
+
+    #[test]
+    fn test_far_apart_cells() {
+        let mut grid = new_grid(Storage::Sparse);
+        grid.set((1_000_000, -1_000_000, 0), 65);
+        grid.set((-5, 7, 0), 66);
+
+        assert_eq!(grid.get((1_000_000, -1_000_000, 0)), 65);
+        assert_eq!(grid.get((-5, 7, 0)), 66);
+        assert_eq!(grid.get((2, 0, 0)), '@' as i64);
+        assert_eq!(grid.get((-6, 7, 0)), ' ' as i64);
+        assert_eq!(bounds(&grid), ((-5, -1_000_000, 0), (1_000_000, 7, 0)));
+        assert_eq!(grid.get_least_point(), (-5, -1_000_000, 0));
+        assert_eq!(grid.get_greatest_point(), (1_000_005, 1_000_007, 0));
+        if let Space::Sparse(sparse) = &grid.space {
+            assert_eq!(sparse.chunk_count(), 3);
+        }
+    }
+
+    #[test]
+    fn test_least_and_greatest_points() {
+        let mut grid = new_grid(Storage::Dense);
+        assert_eq!(grid.get_least_point(), (0, 0, 0));
+        assert_eq!(grid.get_greatest_point(), (2, 0, 0));
+
+        grid.set((-3, 4, 0), 65);
+        grid.set((1, -2, 0), 66);
+        assert_eq!(grid.get_least_point(), (-3, -2, 0));
+        assert_eq!(grid.get_greatest_point(), (5, 6, 0));
+
+        // overwriting a cell does not count it twice
+        grid.set((-3, 4, 0), 67);
+        grid.set((-3, 4, 0), 32);
+        assert_eq!(grid.get_least_point(), (0, -2, 0));
+        assert_eq!(grid.get_greatest_point(), (2, 2, 0));
+
+        for position in [(0, 0, 0), (2, 0, 0), (1, -2, 0)] {
+            grid.set(position, 32);
+        }
+        assert_eq!(grid.get_greatest_point(), (0, 0, 0));
+    }
+
+    #[test]
+    fn test_cells_at_the_limits_of_a_cell() {
+        for storage in [Storage::Sparse, Storage::Auto] {
+            let mut grid = new_grid(storage);
+            let positions = [
+                (i64::MAX, 0, 0),
+                (i64::MIN, 0, 0),
+                (0, i64::MAX, 0),
+                (0, i64::MIN, 0),
+                (0, 0, i64::MAX),
+                (0, 0, i64::MIN),
+                (i64::MAX, i64::MAX, i64::MAX),
+                (i64::MIN, i64::MIN, i64::MIN),
+            ];
+            for (i, position) in positions.into_iter().enumerate() {
+                grid.set(position, 65 + i as i64);
+            }
+            for (i, position) in positions.into_iter().enumerate() {
+                assert!(!grid.out_of_bounds(position));
+                assert_eq!(grid.get(position), 65 + i as i64);
+            }
+            assert_eq!(grid.get((0, 0, 0)), 'v' as i64);
+            assert_eq!(
+                bounds(&grid),
+                (
+                    (i64::MIN, i64::MIN, i64::MIN),
+                    (i64::MAX, i64::MAX, i64::MAX)
+                )
+            );
+            assert_eq!(grid.get_least_point(), (i64::MIN, i64::MIN, i64::MIN));
+            // the extent of Funge-Space wraps around
+            assert_eq!(grid.get_greatest_point(), (-1, -1, -1));
+
+            // erasing the cells shrinks the bounds back
+            for position in positions {
+                grid.set(position, 32);
+                grid.shrink(position);
+            }
+            assert_eq!(bounds(&grid), ((0, 0, 0), (2, 0, 0)));
+        }
+    }
+
+    #[test]
+    fn test_automatic_storage() {
+        let mut grid = new_grid(Storage::Auto);
+        grid.set((100, 100, 0), 65);
+        assert!(!grid.is_sparse());
+
+        grid.set((1_000_000, -1_000_000, 0), 66);
+        assert!(grid.is_sparse());
+        assert_eq!(grid.get((100, 100, 0)), 65);
+        assert_eq!(grid.get((0, 0, 0)), 'v' as i64);
+
+        let mut grid = new_grid(Storage::Dense);
+        grid.set((1_000_000, 0, 0), 66);
+        assert!(!grid.is_sparse());
+    }
+
+    #[test]
+    fn test_sparse_bounds_shrink_like_dense_ones() {
+        let (mut dense, mut sparse) = (new_grid(Storage::Dense), new_grid(Storage::Sparse));
+        let writes = [
+            ((10, 3, 0), 65),
+            ((-4, -2, 0), 66),
+            ((10, 3, 0), 32),
+            ((0, 5, 0), 32),
+            ((2, 0, 0), 32),
+            ((-4, -2, 0), 32),
+            ((0, 0, 0), 32),
+        ];
+        for (position, c) in writes {
+            for grid in [&mut dense, &mut sparse] {
+                grid.set(position, c);
+                if c == 32 {
+                    grid.shrink(position);
+                }
+            }
+            assert_eq!(bounds(&dense), bounds(&sparse));
+            assert_eq!(dense.get_least_point(), sparse.get_least_point());
+            assert_eq!(dense.get_greatest_point(), sparse.get_greatest_point());
+            assert_eq!(dense.resizes(), sparse.resizes());
+        }
+        if let Space::Sparse(sparse) = &sparse.space {
+            assert_eq!(sparse.chunk_count(), 0);
+        }
+    }
+}
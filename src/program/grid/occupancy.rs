@@ -0,0 +1,67 @@
+use std::collections::BTreeMap;
+
+use super::super::fungetypes::FungeInteger;
+
+/**
+The number of non-space cells on every column, row and plane of Funge-Space that holds any,
+kept up to date on every write, so that the least and the greatest point
+of the non-space cells are known without scanning Funge-Space.
+*/
+pub(super) struct Occupancy<T: FungeInteger> {
+    columns: BTreeMap<T, usize>,
+    rows: BTreeMap<T, usize>,
+    planes: BTreeMap<T, usize>,
+}
+
+impl<T: FungeInteger> Default for Occupancy<T> {
+    fn default() -> Self {
+        Self {
+            columns: BTreeMap::new(),
+            rows: BTreeMap::new(),
+            planes: BTreeMap::new(),
+        }
+    }
+}
+
+impl<T: FungeInteger> Occupancy<T> {
+    /// Records that the cell on `(x, y, z)` is no longer a space.
+    pub fn add(&mut self, (x, y, z): (T, T, T)) {
+        *self.columns.entry(x).or_default() += 1;
+        *self.rows.entry(y).or_default() += 1;
+        *self.planes.entry(z).or_default() += 1;
+    }
+
+    /// Records that the cell on `(x, y, z)` is a space again.
+    pub fn remove(&mut self, (x, y, z): (T, T, T)) {
+        for (counts, key) in [
+            (&mut self.columns, x),
+            (&mut self.rows, y),
+            (&mut self.planes, z),
+        ] {
+            if let Some(count) = counts.get_mut(&key) {
+                *count -= 1;
+                if *count == 0 {
+                    counts.remove(&key);
+                }
+            }
+        }
+    }
+
+    /// Returns the least and the greatest position of the non-space cells, if there are any.
+    pub fn non_space_box(&self) -> Option<[(T, T, T); 2]> {
+        let least = |counts: &BTreeMap<T, usize>| counts.keys().next().cloned();
+        let greatest = |counts: &BTreeMap<T, usize>| counts.keys().next_back().cloned();
+        Some([
+            (
+                least(&self.columns)?,
+                least(&self.rows)?,
+                least(&self.planes)?,
+            ),
+            (
+                greatest(&self.columns)?,
+                greatest(&self.rows)?,
+                greatest(&self.planes)?,
+            ),
+        ])
+    }
+}
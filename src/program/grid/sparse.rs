@@ -0,0 +1,218 @@
+use std::collections::HashMap;
+
+use super::super::{bounds::Bounds, fungetypes::FungeInteger};
+use super::dense::DenseGrid;
+
+/// The width of a chunk, in cells.
+const CHUNK_WIDTH: usize = 32;
+/// The height of a chunk, in cells; a chunk always lies on a single plane.
+const CHUNK_HEIGHT: usize = 32;
+
+/// A `CHUNK_WIDTH` x `CHUNK_HEIGHT` rectangle of cells, stored row after row.
+struct Chunk<T> {
+    cells: Vec<T>,
+    /// The number of cells of the chunk that are not spaces
+    non_spaces: usize,
+}
+
+/**
+Funge-Space, stored sparsely: the cells are grouped in fixed-size chunks, which are
+kept in a hash map by their position and only exist while they hold a non-space cell.
+So reading or writing a cell takes the same time however far apart the cells are.
+
+The bounds of Funge-Space grow and shrink exactly like the ones of a `DenseGrid` do.
+*/
+pub(super) struct SparseGrid<T: FungeInteger> {
+    chunks: HashMap<(T, T, T), Chunk<T>>,
+    bounds: Bounds<T>,
+    space: T,
+    chunk_width: T,
+    chunk_height: T,
+    /// The number of times the bounds grew, in any direction
+    growths: u64,
+    /// The number of times the bounds shrank, in any direction
+    shrinks: u64,
+}
+
+impl<T: FungeInteger> From<&DenseGrid<T>> for SparseGrid<T> {
+    fn from(dense: &DenseGrid<T>) -> Self {
+        let bounds = dense.get_bounds();
+        let (growths, shrinks) = dense.resizes();
+        let mut sparse = Self {
+            chunks: HashMap::new(),
+            bounds: Bounds::new(
+                (bounds.lower_x(), bounds.lower_y(), bounds.lower_z()),
+                (bounds.upper_x(), bounds.upper_y(), bounds.upper_z()),
+            ),
+            space: T::from(32).unwrap(),
+            chunk_width: T::from(CHUNK_WIDTH).unwrap(),
+            chunk_height: T::from(CHUNK_HEIGHT).unwrap(),
+            growths,
+            shrinks,
+        };
+        for (position, c) in dense.non_space_cells() {
+            sparse.set(position, c);
+        }
+        sparse
+    }
+}
+
+impl<T: FungeInteger> SparseGrid<T> {
+    pub fn get_bounds(&self) -> &Bounds<T> {
+        &self.bounds
+    }
+
+    /// Returns the number of times the bounds grew and the number of times they shrank.
+    pub fn resizes(&self) -> (u64, u64) {
+        (self.growths, self.shrinks)
+    }
+
+    /// Returns the position of the chunk of the cell on `(x, y, z)` and the index of the cell in it.
+    fn locate(&self, (x, y, z): (T, T, T)) -> ((T, T, T), usize) {
+        let (chunk_x, i) = div_floor(x, &self.chunk_width);
+        let (chunk_y, j) = div_floor(y, &self.chunk_height);
+        ((chunk_x, chunk_y, z), j * CHUNK_WIDTH + i)
+    }
+
+    /// Returns the value of the cell on `position`; a space, if it was never written to.
+    pub fn get(&self, position: (T, T, T)) -> T {
+        let (chunk, i) = self.locate(position);
+        match self.chunks.get(&chunk) {
+            Some(chunk) => chunk.cells[i].clone(),
+            None => self.space.clone(),
+        }
+    }
+
+    /// Sets the value of the cell on `position` and grows the bounds to include it.
+    pub fn set(&mut self, position: (T, T, T), c: T) {
+        self.grow(&position);
+        let (key, i) = self.locate(position);
+        if c == self.space {
+            if let Some(chunk) = self.chunks.get_mut(&key) {
+                if chunk.cells[i] != self.space {
+                    chunk.cells[i] = c;
+                    chunk.non_spaces -= 1;
+                    // a chunk of spaces is no different from a missing one
+                    if chunk.non_spaces == 0 {
+                        self.chunks.remove(&key);
+                    }
+                }
+            }
+        } else {
+            let space = &self.space;
+            let chunk = self.chunks.entry(key).or_insert_with(|| Chunk {
+                cells: vec![space.clone(); CHUNK_WIDTH * CHUNK_HEIGHT],
+                non_spaces: 0,
+            });
+            if chunk.cells[i] == *space {
+                chunk.non_spaces += 1;
+            }
+            chunk.cells[i] = c;
+        }
+    }
+
+    /// Grows the bounds, so that they include `position`; every axis on which they grow counts as a growth.
+    fn grow(&mut self, (x, y, z): &(T, T, T)) {
+        let bounds = &mut self.bounds;
+        if *x < bounds.lower_x() {
+            bounds.set_lower_x(x.clone());
+            self.growths += 1;
+        } else if *x > bounds.upper_x() {
+            bounds.set_upper_x(x.clone());
+            self.growths += 1;
+        }
+        if *y < bounds.lower_y() {
+            bounds.set_lower_y(y.clone());
+            self.growths += 1;
+        } else if *y > bounds.upper_y() {
+            bounds.set_upper_y(y.clone());
+            self.growths += 1;
+        }
+        if *z < bounds.lower_z() {
+            bounds.set_lower_z(z.clone());
+            self.growths += 1;
+        } else if *z > bounds.upper_z() {
+            bounds.set_upper_z(z.clone());
+            self.growths += 1;
+        }
+    }
+
+    /**
+    Shrinks the bounds, after a space was put on `(x, y, z)`: on every axis on which the cell
+    lies on an edge of the bounds, that edge moves to the farthest non-space cell,
+    as given by `non_space_box`. The bounds never shrink to less than a single cell.
+    */
+    pub fn shrink(&mut self, (x, y, z): (T, T, T), non_space_box: Option<[(T, T, T); 2]>) {
+        let (least, greatest) = match non_space_box {
+            Some([least, greatest]) => (Some(least), Some(greatest)),
+            None => (None, None),
+        };
+        let bounds = &mut self.bounds;
+        let mut shrank = false;
+
+        macro_rules! shrink_axis {
+            ($c:expr, $field:tt, $lower:ident, $upper:ident, $set_lower:ident, $set_upper:ident) => {
+                if $c == bounds.$upper() {
+                    let upper = match &greatest {
+                        Some(greatest) => greatest.$field.clone(),
+                        None => bounds.$lower(),
+                    };
+                    if upper != bounds.$upper() {
+                        bounds.$set_upper(upper);
+                        shrank = true;
+                    }
+                } else if $c == bounds.$lower() {
+                    let lower = match &least {
+                        Some(least) => least.$field.clone(),
+                        None => bounds.$upper(),
+                    };
+                    if lower != bounds.$lower() {
+                        bounds.$set_lower(lower);
+                        shrank = true;
+                    }
+                }
+            };
+        }
+        shrink_axis!(z, 2, lower_z, upper_z, set_lower_z, set_upper_z);
+        shrink_axis!(y, 1, lower_y, upper_y, set_lower_y, set_upper_y);
+        shrink_axis!(x, 0, lower_x, upper_x, set_lower_x, set_upper_x);
+
+        if shrank {
+            self.shrinks += 1;
+        }
+    }
+
+    #[cfg(test)]
+    /// Returns the number of chunks that are currently allocated
+    pub fn chunk_count(&self) -> usize {
+        self.chunks.len()
+    }
+}
+
+/// Divides `n` by `d` (which is positive), rounding down, and returns the quotient and the remainder.
+fn div_floor<T: FungeInteger>(n: T, d: &T) -> (T, usize) {
+    let quotient = n.clone() / d.clone();
+    let remainder = n - quotient.clone() * d.clone();
+    if remainder < T::zero() {
+        (
+            quotient - T::one(),
+            (remainder + d.clone()).to_usize().unwrap_or_default(),
+        )
+    } else {
+        (quotient, remainder.to_usize().unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_div_floor() {
+        assert_eq!(div_floor(70, &32), (2, 6));
+        assert_eq!(div_floor(0, &32), (0, 0));
+        assert_eq!(div_floor(-1, &32), (-1, 31));
+        assert_eq!(div_floor(-32, &32), (-1, 0));
+        assert_eq!(div_floor(-33, &32), (-2, 31));
+    }
+}
@@ -0,0 +1,120 @@
+use chrono::{DateTime, Utc};
+use std::rc::Rc;
+
+use super::{cursor::Cursor, fingerprints::Fingerprint, fungetypes::FungeInteger, sstack::SStack};
+
+/**
+An instruction pointer (IP) of a concurrent Funge-98 program.
+
+Every IP carries its own `cursor` (position, delta and storage offset),
+its own stack stack, its own string mode flag, its own semantics
+for the `A`-`Z` instructions and its own `HRTI` timer mark; only Funge-Space is shared among them.
+*/
+#[derive(Default, Clone)]
+pub(super) struct InstructionPointer<T: FungeInteger> {
+    pub id: usize,
+    pub team: usize,
+    pub cursor: Cursor<T>,
+    pub sstack: SStack<T>,
+    pub string_mode: bool,
+    /// The mark of the timer of the `HRTI` fingerprint
+    pub timer_mark: Option<DateTime<Utc>>,
+    /// One stack of (fingerprint) semantics per instruction `A`-`Z`
+    semantics: [Vec<Rc<dyn Fingerprint<T>>>; 26],
+}
+
+impl<T: FungeInteger> InstructionPointer<T> {
+    /**
+    Creates the child of this IP, as the `t` (split) instruction requires:
+    an exact copy of its parent, with the given `id` and its delta reversed.
+    */
+    pub fn split(&self, id: usize) -> Self {
+        let mut child = self.clone();
+        child.id = id;
+        child.cursor.reflect();
+        child
+    }
+
+    /// Pushes the semantics of `fingerprint` onto the stacks of the instructions it provides.
+    pub fn load_semantics(&mut self, fingerprint: Rc<dyn Fingerprint<T>>) {
+        for instruction in fingerprint.instructions().chars() {
+            self.semantics[semantics_index(instruction)].push(Rc::clone(&fingerprint));
+        }
+    }
+
+    /**
+    Pops the semantics of the instructions `fingerprint` provides,
+    regardless of the fingerprint they were loaded from.
+    */
+    pub fn unload_semantics(&mut self, fingerprint: &dyn Fingerprint<T>) {
+        for instruction in fingerprint.instructions().chars() {
+            self.semantics[semantics_index(instruction)].pop();
+        }
+    }
+
+    /**
+    Returns the fingerprint that currently provides the semantics of `instruction`,
+    if `instruction` is one of `A`-`Z` and such a fingerprint is loaded.
+    */
+    pub fn semantics(&self, instruction: char) -> Option<Rc<dyn Fingerprint<T>>> {
+        if instruction.is_ascii_uppercase() {
+            self.semantics[semantics_index(instruction)].last().cloned()
+        } else {
+            None
+        }
+    }
+}
+
+/// Maps an `A`-`Z` instruction to its semantics stack.
+fn semantics_index(instruction: char) -> usize {
+    (instruction as u8 - b'A') as usize
+}
+
+/// A read-only view of an IP, for inspecting the state of a program.
+pub struct IpState<'a, T: FungeInteger>(pub(super) &'a InstructionPointer<T>);
+
+impl<'a, T: FungeInteger> IpState<'a, T> {
+    /// Returns the ID of the IP, as reported by `y`.
+    pub fn id(&self) -> usize {
+        self.0.id
+    }
+
+    /// Returns the position of the IP i.e., of the instruction it executes next.
+    pub fn position(&self) -> (T, T, T) {
+        self.0.cursor.position()
+    }
+
+    /// Returns the delta of the IP.
+    pub fn delta(&self) -> (T, T, T) {
+        self.0.cursor.delta_members()
+    }
+
+    /// Returns the storage offset of the IP.
+    pub fn storage_offset(&self) -> (T, T, T) {
+        self.0.cursor.storage_offset()
+    }
+
+    /// Returns whether the IP is in string mode.
+    pub fn string_mode(&self) -> bool {
+        self.0.string_mode
+    }
+
+    /**
+    Returns the stack stack of the IP, bottom first; so the TOSS is the last stack
+    and, on every stack, the topmost value is the last one.
+    */
+    pub fn stacks(&self) -> &'a [Vec<T>] {
+        self.0.sstack.get_stacks()
+    }
+
+    /// Returns the TOSS of the IP, with its topmost value last.
+    pub fn toss(&self) -> &'a [T] {
+        self.stacks().last().map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// Returns the SOSS of the IP, with its topmost value last, if the IP has one.
+    pub fn soss(&self) -> Option<&'a [T]> {
+        let stacks = self.stacks();
+        (stacks.len() > 1).then(|| stacks[stacks.len() - 2].as_slice())
+    }
+}
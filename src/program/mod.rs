@@ -1,28 +1,205 @@
 use anyhow::{Context, Error, Result};
 use bstr::ByteSlice;
-use std::{default::Default, fs, path::PathBuf};
+use std::{default::Default, fs, path::PathBuf, rc::Rc};
 
 use bounds::Bounds;
-use cursor::Cursor;
+pub use builder::ProgramBuilder;
+pub use clock::{Clock, SystemClock};
+pub use dialect::Dialect;
+pub use engine::Engine;
+pub use exit::Exit;
+use fingerprints::Registry;
+pub use format::{format_value, format_vector};
 use fungetypes::FungeInteger;
 use grid::Grid;
-use sstack::SStack;
+use ip::InstructionPointer;
+pub use ip::IpState;
+pub use paradigm::Paradigm;
+pub use profile::Profile;
+use runs::RunCache;
+pub use step::Step;
+pub use storage::Storage;
+use streams::Streams;
+pub use trace::Trace;
+
+// Warnings are best-effort; failing to write one to stderr is not a runtime error
+macro_rules! warning {
+    ($self:ident, $message:expr) => {
+        let _ = writeln!($self.streams.stderr, "warning: {}", $message);
+    };
+}
 
 mod bounds;
+mod builder;
+mod clock;
+mod compiler;
 mod cursor;
 mod delta;
+mod dialect;
+mod engine;
+mod exit;
+mod fileio;
+mod fingerprints;
+mod format;
 pub(super) mod fungetypes;
 mod grid;
+mod ip;
+mod paradigm;
+mod profile;
+mod runs;
 mod sstack;
+mod step;
+mod storage;
+mod streams;
 mod sysinfo;
+mod trace;
 mod vm;
 
-#[derive(Default)]
+/**
+A Funge program, with cells (and stack elements) of type `T`,
+along with the whole state of its execution.
+*/
 pub struct Program<T: FungeInteger> {
     grid: Grid<T>,
-    cursor: Cursor<T>,
-    sstack: SStack<T>,
-    string_mode: bool,
+    dialect: Dialect,
+    ips: Vec<InstructionPointer<T>>,
+    current_ip: usize,
+    next_ip_id: usize,
+    paradigm: Paradigm,
+    fingerprints: Registry<T>,
+    clock: Rc<dyn Clock>,
+    /// The vectors stored by the `REFC` fingerprint, shared among all IPs
+    references: Vec<(T, T, T)>,
+    streams: Streams,
+    /// The positions of the cells written to during the current `step`, if one is in progress
+    written_cells: Option<Vec<(T, T, T)>>,
+    /// The number of the current tick
+    tick: u64,
+    trace: Option<Trace<T>>,
+    profile: Option<Profile<T>>,
+    /// The runs of simple instructions cached so far, if the fast engine executes the program
+    runs: Option<RunCache<T>>,
+}
+
+impl<T: FungeInteger> Default for Program<T> {
+    /// A program starts its execution with a single IP, with ID 0.
+    fn default() -> Self {
+        Self {
+            grid: Grid::default(),
+            dialect: Dialect::default(),
+            ips: vec![InstructionPointer::default()],
+            current_ip: 0,
+            next_ip_id: 1,
+            paradigm: Paradigm::default(),
+            fingerprints: Registry::default(),
+            clock: Rc::new(SystemClock),
+            references: Vec::new(),
+            streams: Streams::default(),
+            written_cells: None,
+            tick: 0,
+            trace: None,
+            profile: None,
+            runs: None,
+        }
+    }
+}
+
+impl<T: FungeInteger> Program<T> {
+    /// Starts building a `Program` out of its `source` code; see `ProgramBuilder`.
+    pub fn builder(source: impl AsRef<[u8]>) -> ProgramBuilder<T> {
+        ProgramBuilder::new(source)
+    }
+
+    /**
+    Constructs a `Program` from the raw contents of a source file, in `dialect`.
+
+    Form feeds start a new plane in Trefunge and are ignored otherwise;
+    in Unefunge, EOLs are ignored as well. In Befunge-93, the lines beyond
+    the 25th and the characters beyond the 80th of each line are dropped.
+    */
+    pub fn from_source(contents: &[u8], dialect: Dialect) -> Self {
+        let mut program = Program::from(split_source(contents, dialect));
+        program.dialect = dialect;
+        program
+    }
+
+    /**
+    Constructs a `Program` from a source file, in `dialect`
+    or, if it is `None`, in the dialect the extension of the file suggests.
+    */
+    pub fn from_file(filename: PathBuf, dialect: Option<Dialect>) -> Result<Self> {
+        let dialect = dialect.unwrap_or_else(|| Dialect::from_path(&filename));
+        let contents = fs::read(filename).context("Failed to read Befunge source file")?;
+        Ok(Program::from_source(&contents, dialect))
+    }
+
+    /// Sets the operating paradigm of the `=` (execute) instruction.
+    pub fn set_paradigm(&mut self, paradigm: Paradigm) {
+        self.paradigm = paradigm;
+    }
+
+    /// Returns the dialect of the program.
+    pub fn dialect(&self) -> Dialect {
+        self.dialect
+    }
+
+    /// Returns the value of the cell at `position`; a space, if it lies outside of Funge-Space.
+    pub fn cell(&self, position: (T, T, T)) -> T {
+        self.get_cell(position)
+    }
+
+    /**
+    Returns the bounds of Funge-Space: its least and its greatest point, both inclusive
+    (the greatest one lies right before the least one on the axes on which Funge-Space is empty).
+    */
+    pub fn bounds(&self) -> ((T, T, T), (T, T, T)) {
+        let bounds = self.grid.get_bounds();
+        (
+            (bounds.lower_x(), bounds.lower_y(), bounds.lower_z()),
+            (bounds.upper_x(), bounds.upper_y(), bounds.upper_z()),
+        )
+    }
+
+    /// Sets the value of the cell at `position`, growing Funge-Space if necessary.
+    pub fn set_cell(&mut self, position: (T, T, T), value: T) {
+        self.put_cell(position, value);
+    }
+
+    /// Returns the IP that executes next, unless all IPs have ended.
+    pub fn current_ip(&self) -> Option<IpState<'_, T>> {
+        self.ips.get(self.current_ip).map(IpState)
+    }
+
+    /**
+    Returns the stack stack of the IP that executes next (bottom first, like `IpState::stacks`),
+    for editing it; unless all IPs have ended.
+    */
+    pub fn current_stacks_mut(&mut self) -> Option<&mut Vec<Vec<T>>> {
+        let current_ip = self.current_ip;
+        self.ips
+            .get_mut(current_ip)
+            .map(|ip| ip.sstack.get_stacks_mut())
+    }
+
+    /// Returns all the IPs, in the order they execute within a tick.
+    pub fn ips(&self) -> impl Iterator<Item = IpState<'_, T>> {
+        self.ips.iter().map(IpState)
+    }
+
+    /// Returns the profile of the execution so far, if the program is profiled.
+    pub fn profile(&self) -> Option<&Profile<T>> {
+        self.profile.as_ref()
+    }
+
+    /// Returns the IP that is currently executing.
+    fn ip(&self) -> &InstructionPointer<T> {
+        &self.ips[self.current_ip]
+    }
+
+    /// Returns the IP that is currently executing, mutably.
+    fn ip_mut(&mut self) -> &mut InstructionPointer<T> {
+        &mut self.ips[self.current_ip]
+    }
 }
 
 impl<T: FungeInteger> From<Vec<Vec<u8>>> for Program<T> {
@@ -30,30 +207,57 @@ impl<T: FungeInteger> From<Vec<Vec<u8>>> for Program<T> {
     Constucts a `Program` from a `Vec` of `String`s i.e.,
     the lines of the Befunge source code.
     */
+    fn from(code: Vec<Vec<u8>>) -> Self {
+        Program::from(vec![code])
+    }
+}
 
-    fn from(mut code: Vec<Vec<u8>>) -> Self {
-        let width = code.iter().map(|line| line.len()).max().unwrap_or(1);
-        let height: T = T::from(code.len()).unwrap_or_default();
+impl<T: FungeInteger> From<Vec<Vec<Vec<u8>>>> for Program<T> {
+    /**
+    Constucts a `Program` from the planes of the source code,
+    each one of them a `Vec` of lines.
+    */
+    fn from(mut code: Vec<Vec<Vec<u8>>>) -> Self {
+        let width = code
+            .iter()
+            .flatten()
+            .map(|line| line.len())
+            .max()
+            .unwrap_or(1);
+        let height = code
+            .iter()
+            .map(|plane| plane.len())
+            .max()
+            .unwrap_or_default();
+        let depth = code.len();
 
-        // make all lines have the same width
-        for line in &mut code {
-            line.resize(width, 32);
+        // make all planes have the same height and all lines have the same width
+        for plane in &mut code {
+            plane.resize(height, vec![]);
+            for line in plane.iter_mut() {
+                line.resize(width, 32);
+            }
         }
 
-        let source: Vec<Vec<T>> = code
+        let source: Vec<Vec<Vec<T>>> = code
             .iter()
-            .map(|line| {
-                line.iter()
-                    .map(|b| T::from(*b).unwrap_or_default())
+            .map(|plane| {
+                plane
+                    .iter()
+                    .map(|line| {
+                        line.iter()
+                            .map(|b| T::from(*b).unwrap_or_default())
+                            .collect()
+                    })
                     .collect()
             })
             .collect();
 
+        // the greatest point lies right before the origin on the axes with no cells
+        let greatest = |length: usize| T::from(length).unwrap_or_default() - T::one();
         let bounds = Bounds::new(
-            T::zero(),
-            T::zero(),
-            T::from(width).unwrap_or_default(),
-            height,
+            (T::zero(), T::zero(), T::zero()),
+            (greatest(width), greatest(height), greatest(depth)),
         );
 
         let grid = Grid::from((source, bounds));
@@ -66,34 +270,74 @@ impl<T: FungeInteger> From<Vec<Vec<u8>>> for Program<T> {
 }
 
 impl<T: FungeInteger> TryFrom<PathBuf> for Program<T> {
-    /// Constructs a `Program` from the contents of a Befunge source code file.
+    /**
+    Constructs a `Program` from the contents of a Funge source code file,
+    in the dialect its extension suggests.
+    */
     type Error = Error;
 
     fn try_from(filename: PathBuf) -> Result<Self> {
-        // step 1: read raw bytes from file
-        let contents = fs::read(filename).context("Failed to read Befunge source file")?;
-        // step 2: split in lines by...
-        let code: Vec<&[u8]> = contents
-            .split_str("\r\n") // ...\r\n
-            .flat_map(|line| line.split_str("\r")) // ...\r
-            .flat_map(|line| line.split_str("\n")) // ... and \n
-            .collect();
-        // step 3: collect and remove form feed characters
-        let code: Vec<Vec<u8>> = code
-            .iter()
-            .map(|line| {
-                line.to_vec()
-                    .iter()
-                    .filter(|b| **b != 12)
-                    .copied()
-                    .collect()
-            })
-            .collect();
+        Program::from_file(filename, None)
+    }
+}
 
-        Ok(Program::from(code))
+/// Splits the raw contents of a source file of `dialect` in planes of lines.
+fn split_source(contents: &[u8], dialect: Dialect) -> Vec<Vec<Vec<u8>>> {
+    match dialect {
+        Dialect::Unefunge => vec![vec![split_lines(contents).concat()]],
+        Dialect::Befunge => vec![split_lines(contents)],
+        Dialect::Befunge93 => {
+            // the source code is cut or padded to the fixed size of Funge-Space
+            let (width, height) = dialect.fixed_size().unwrap_or_default();
+            let mut lines = split_lines(contents);
+            lines.resize(height, vec![]);
+            for line in lines.iter_mut() {
+                line.resize(width, b' ');
+            }
+            vec![lines]
+        }
+        Dialect::Trefunge => split_planes(contents),
     }
 }
 
+/**
+Splits the raw contents of a Befunge source file in lines,
+by `\r\n`, `\r` and `\n`, and removes all form feed characters.
+*/
+fn split_lines(contents: &[u8]) -> Vec<Vec<u8>> {
+    contents
+        .split_str("\r\n") // ...\r\n
+        .flat_map(|line| line.split_str("\r")) // ...\r
+        .flat_map(|line| line.split_str("\n")) // ... and \n
+        .map(|line| line.iter().filter(|b| **b != 12).copied().collect())
+        .collect()
+}
+
+/**
+Splits the raw contents of a Trefunge source file in planes, by form feeds,
+and then each plane in lines.
+
+A form feed usually lies on a line of its own, so the EOLs right before
+and right after it do not add an empty line to either plane.
+*/
+fn split_planes(contents: &[u8]) -> Vec<Vec<Vec<u8>>> {
+    contents
+        .split_str("\x0c")
+        .enumerate()
+        .map(|(z, plane)| {
+            let plane = match plane {
+                [b'\r', b'\n', rest @ ..] | [b'\r' | b'\n', rest @ ..] if z > 0 => rest,
+                _ => plane,
+            };
+            let mut lines = split_lines(plane);
+            if lines.last().is_some_and(|line| line.is_empty()) {
+                lines.pop();
+            }
+            lines
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -107,10 +351,57 @@ mod tests {
             vec![],
             b"12345".to_vec(),
         ]);
-        let width = program.grid[0].len();
-        for i in 0..program.grid.len() {
-            ensure!(program.grid[i as i32].len() == width);
+        ensure!(program.grid.row_widths() == vec![8; 4]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_split_planes() -> Result<()> {
+        let planes = split_planes(b"ab\r\n\x0c\r\ncd\n\n\x0cef");
+        ensure!(
+            planes
+                == vec![
+                    vec![b"ab".to_vec()],
+                    vec![b"cd".to_vec(), vec![]],
+                    vec![b"ef".to_vec()]
+                ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_storage_offset_has_one_component_per_dimension() -> Result<()> {
+        let mut program = Program::<i32>::from_source(b"{q", Dialect::Trefunge);
+        program.push(0);
+        program.ip_mut().cursor.set_storage_offset((4, 5, 6));
+        program.run()?;
+        ensure!(program.ip().sstack.get_stacks()[0] == vec![4, 5, 6]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_report_cells_match_full_report() -> Result<()> {
+        use sysinfo::SystemInfoReporter;
+
+        let program = Program::<i32>::from_source(b"@", Dialect::Befunge);
+        let report = program.get_full_report();
+        // the first cells come before the environment variables, which can be many
+        for n in (1..=64).chain([report.len()]) {
+            ensure!(program.get_report_cell(n) == Ok(report[report.len() - n]));
         }
+        ensure!(program.get_report_cell(report.len() + 1) == Err(report.len()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_befunge_93_space_is_80x25() -> Result<()> {
+        let long_line = [b'>'; 100];
+        let program = Program::<i32>::from_source(&long_line, Dialect::Befunge93);
+        ensure!(program.grid.row_widths() == vec![80; 25]);
 
         Ok(())
     }
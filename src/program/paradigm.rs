@@ -0,0 +1,68 @@
+use std::{io, process::Command};
+
+/**
+The operating paradigm of the `=` (execute) instruction i.e.,
+the way in which the commands it pops are run.
+
+Since running arbitrary commands is dangerous, `=` is disabled by default.
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Paradigm {
+    /// `=` is unavailable and reflects
+    #[default]
+    Disabled,
+    /// Commands are interpreted by the system shell, like the C `system()` call does
+    System,
+    /// Commands are split by whitespace and executed directly, without a shell
+    Exec,
+}
+
+impl Paradigm {
+    /// Returns the value of this paradigm, as reported by `y` (item 5).
+    pub fn id(&self) -> u8 {
+        match self {
+            Paradigm::Disabled => 0,
+            Paradigm::System => 1,
+            Paradigm::Exec => 2,
+        }
+    }
+
+    /**
+    Runs `command` according to this paradigm and returns its exit status.
+
+    Fails if the paradigm is `Disabled` or if the command could not be run at all.
+    A command that was terminated by a signal has an exit status of -1.
+    */
+    pub fn execute(&self, command: &str) -> io::Result<i32> {
+        let mut process = match self {
+            Paradigm::Disabled => {
+                return Err(io::Error::new(
+                    io::ErrorKind::PermissionDenied,
+                    "the = instruction is disabled",
+                ))
+            }
+            Paradigm::System => {
+                if cfg!(windows) {
+                    let mut process = Command::new("cmd");
+                    process.arg("/C").arg(command);
+                    process
+                } else {
+                    let mut process = Command::new("sh");
+                    process.arg("-c").arg(command);
+                    process
+                }
+            }
+            Paradigm::Exec => {
+                let mut words = command.split_whitespace();
+                let program = words
+                    .next()
+                    .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "empty command"))?;
+                let mut process = Command::new(program);
+                process.args(words);
+                process
+            }
+        };
+        let status = process.status()?;
+        Ok(status.code().unwrap_or(-1))
+    }
+}
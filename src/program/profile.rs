@@ -0,0 +1,408 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io::{self, Write},
+    time::Duration,
+};
+
+use super::{
+    format::{format_value, format_vector, json_vector},
+    fungetypes::FungeInteger,
+};
+
+/// The shades of the text heat map, from the coldest to the hottest cell.
+const SHADES: [char; 5] = [' ', '░', '▒', '▓', '█'];
+/// The number of the hottest cells that the summary lists.
+const HOTTEST_CELLS: usize = 10;
+/**
+The longest run of empty rows, columns or planes between executed cells that the heat map
+and the matrices cover; a longer one is left out (and drawn as a single gap in the heat map).
+*/
+const MAX_GAP: usize = 16;
+/// The shades of the gap rows and columns of the text heat map.
+const GAP_ROW: char = '┈';
+const GAP_COLUMN: char = '┊';
+
+/**
+A profile of the execution of a program: how many times each cell and each instruction
+was executed, how many ticks the program ran for, how long it waited for input
+and how many times Funge-Space was resized.
+
+The profile can be reported as a summary table, as a text heat map of Funge-Space
+and as a matrix of the executions of the cells, in CSV or JSON.
+*/
+pub struct Profile<T: FungeInteger> {
+    dimensions: usize,
+    /// The number of executions of each cell, along with the instruction it executed last
+    cells: BTreeMap<(T, T, T), (u64, T)>,
+    instructions: BTreeMap<T, u64>,
+    pub(super) ticks: u64,
+    pub(super) input_time: Duration,
+    pub(super) growths: u64,
+    pub(super) shrinks: u64,
+}
+
+impl<T: FungeInteger> Profile<T> {
+    pub(super) fn new(dimensions: usize) -> Self {
+        Self {
+            dimensions,
+            cells: BTreeMap::new(),
+            instructions: BTreeMap::new(),
+            ticks: 0,
+            input_time: Duration::ZERO,
+            growths: 0,
+            shrinks: 0,
+        }
+    }
+
+    /// Records an execution of the instruction `value`, on the cell at `position`.
+    pub(super) fn record(&mut self, position: (T, T, T), value: T) {
+        *self.instructions.entry(value.clone()).or_default() += 1;
+        let cell = self.cells.entry(position).or_insert((0, T::zero()));
+        cell.0 += 1;
+        cell.1 = value;
+    }
+
+    /// Returns the number of times each cell was executed; the cells that were not are missing.
+    pub fn cells(&self) -> impl Iterator<Item = (&(T, T, T), u64)> {
+        self.cells
+            .iter()
+            .map(|(position, (count, _))| (position, *count))
+    }
+
+    /// Returns the number of times each instruction was executed.
+    pub fn instructions(&self) -> &BTreeMap<T, u64> {
+        &self.instructions
+    }
+
+    /// Returns the number of instructions that were executed, by all IPs.
+    pub fn executions(&self) -> u64 {
+        self.instructions.values().sum()
+    }
+
+    /// Returns the number of ticks the program ran for.
+    pub fn ticks(&self) -> u64 {
+        self.ticks
+    }
+
+    /// Returns the time that `&` and `~` spent waiting for input.
+    pub fn input_time(&self) -> Duration {
+        self.input_time
+    }
+
+    /// Returns the number of times Funge-Space grew and the number of times it shrank.
+    pub fn resizes(&self) -> (u64, u64) {
+        (self.growths, self.shrinks)
+    }
+
+    /**
+    Writes a summary of the profile: its totals, the executions of each instruction
+    (the most frequent first) and the hottest cells, followed by the text heat map.
+    */
+    pub fn write_summary(&self, output: &mut impl Write) -> io::Result<()> {
+        let executions = self.executions();
+        writeln!(output, "Profile")?;
+        writeln!(output, "  ticks                   {}", self.ticks)?;
+        writeln!(output, "  instructions executed   {}", executions)?;
+        writeln!(
+            output,
+            "  time blocked on input   {:.3}s",
+            self.input_time.as_secs_f64()
+        )?;
+        writeln!(output, "  Funge-Space growths     {}", self.growths)?;
+        writeln!(output, "  Funge-Space shrinks     {}", self.shrinks)?;
+
+        writeln!(output)?;
+        writeln!(
+            output,
+            "{:<14}{:>12}{:>9}",
+            "Instruction", "Executions", "Share"
+        )?;
+        let mut instructions: Vec<(&T, &u64)> = self.instructions.iter().collect();
+        // the sort is stable, so equally frequent instructions stay in order of value
+        instructions.sort_by_key(|(_, count)| std::cmp::Reverse(**count));
+        for (instruction, count) in instructions {
+            writeln!(
+                output,
+                "{:<14}{:>12}{:>8.2}%",
+                format_value(instruction),
+                count,
+                100.0 * *count as f64 / executions as f64
+            )?;
+        }
+
+        writeln!(output)?;
+        writeln!(
+            output,
+            "{:<24}{:<18}{:>12}",
+            "Cell", "Last instruction", "Executions"
+        )?;
+        let mut cells: Vec<_> = self.cells.iter().collect();
+        cells.sort_by_key(|(_, (count, _))| std::cmp::Reverse(*count));
+        for (position, (count, instruction)) in cells.into_iter().take(HOTTEST_CELLS) {
+            writeln!(
+                output,
+                "{:<24}{:<18}{:>12}",
+                format_vector(position, self.dimensions),
+                format_value(instruction),
+                count
+            )?;
+        }
+
+        writeln!(output)?;
+        self.write_heat_map(output)
+    }
+
+    /**
+    Writes the heat map of the region of Funge-Space that was executed, as text:
+    the more times a cell was executed, the darker its shade (in a logarithmic scale).
+    Runs of more than 16 empty rows or columns are drawn as a single gap.
+    */
+    pub fn write_heat_map(&self, output: &mut impl Write) -> io::Result<()> {
+        let ([least, greatest], [columns, rows, planes]) = match (self.region(), self.axes()) {
+            (Some(region), Some(axes)) => (region, axes),
+            _ => return writeln!(output, "No cells were executed"),
+        };
+        let max = self
+            .cells()
+            .map(|(_, count)| count)
+            .max()
+            .unwrap_or_default();
+        writeln!(
+            output,
+            "Heat map from {} to {} ('{}' to '{}' is 1 to {} executions)",
+            format_vector(&least, self.dimensions),
+            format_vector(&greatest, self.dimensions),
+            SHADES[1],
+            SHADES[SHADES.len() - 1],
+            max
+        )?;
+        for (i, z) in planes.iter().flatten().enumerate() {
+            if self.dimensions == 3 {
+                if i > 0 {
+                    writeln!(output)?;
+                }
+                writeln!(output, "z = {}", z)?;
+            }
+            for y in &rows {
+                let row: String = columns
+                    .iter()
+                    .map(|x| match (x, y) {
+                        (Some(x), Some(y)) => {
+                            shade(self.count(x.clone(), y.clone(), z.clone()), max)
+                        }
+                        (_, None) => GAP_ROW,
+                        (None, _) => GAP_COLUMN,
+                    })
+                    .collect();
+                writeln!(output, "{}", row)?;
+            }
+        }
+        Ok(())
+    }
+
+    /**
+    Writes the executions of the cells of the region of Funge-Space that was executed,
+    as a CSV matrix: its first row holds the x coordinates and its first column
+    the y coordinates of the cells. The planes of Trefunge programs follow one another,
+    separated by an empty line, with their z coordinate in their first cell (like `z=0`).
+    Runs of more than 16 empty rows, columns or planes are left out.
+    */
+    pub fn write_csv(&self, output: &mut impl Write) -> io::Result<()> {
+        let [columns, rows, planes] = match self.axes() {
+            Some(axes) => axes,
+            None => return Ok(()),
+        };
+        for (i, z) in planes.iter().flatten().enumerate() {
+            if i > 0 {
+                writeln!(output)?;
+            }
+            let corner = if self.dimensions == 3 {
+                format!("z={}", z)
+            } else {
+                String::new()
+            };
+            let header: Vec<String> = columns.iter().flatten().map(|x| x.to_string()).collect();
+            writeln!(output, "{},{}", corner, header.join(","))?;
+            for y in rows.iter().flatten() {
+                let counts: Vec<String> = columns
+                    .iter()
+                    .flatten()
+                    .map(|x| self.count(x.clone(), y.clone(), z.clone()).to_string())
+                    .collect();
+                writeln!(output, "{},{}", y, counts.join(","))?;
+            }
+        }
+        Ok(())
+    }
+
+    /**
+    Writes the executions of the cells of the region of Funge-Space that was executed,
+    as a JSON object: its `origin` is the least position of the region, its `counts`
+    are nested arrays, one level per dimension (planes, then rows, then cells),
+    and its `coordinates` hold the coordinates of the entries of `counts` along each axis
+    (x first), since runs of more than 16 empty rows, columns or planes are left out.
+    */
+    pub fn write_json(&self, output: &mut impl Write) -> io::Result<()> {
+        let ([least, _], axes) = match (self.region(), self.axes()) {
+            (Some(region), Some(axes)) => (region, axes),
+            _ => {
+                return writeln!(
+                    output,
+                    "{{\"origin\":null,\"counts\":[],\"coordinates\":[]}}"
+                )
+            }
+        };
+        let json_array = |items: Vec<String>| format!("[{}]", items.join(","));
+        // the gaps are left out
+        let [xs, ys, zs] = axes.map(|axis| axis.into_iter().flatten().collect::<Vec<_>>());
+        let planes: Vec<String> = zs
+            .iter()
+            .map(|z| {
+                let rows: Vec<String> = ys
+                    .iter()
+                    .map(|y| {
+                        json_array(
+                            xs.iter()
+                                .map(|x| self.count(x.clone(), y.clone(), z.clone()).to_string())
+                                .collect(),
+                        )
+                    })
+                    .collect();
+                match self.dimensions {
+                    // a Unefunge program has a single row
+                    1 => rows.into_iter().next().unwrap_or_default(),
+                    _ => json_array(rows),
+                }
+            })
+            .collect();
+        let counts = match self.dimensions {
+            3 => json_array(planes),
+            // a Unefunge or Befunge program has a single plane
+            _ => planes.into_iter().next().unwrap_or_default(),
+        };
+        let coordinates: Vec<String> = [xs, ys, zs]
+            .iter()
+            .take(self.dimensions)
+            .map(|axis| json_array(axis.iter().map(|c| c.to_string()).collect()))
+            .collect();
+        writeln!(
+            output,
+            "{{\"origin\":{},\"counts\":{},\"coordinates\":{}}}",
+            json_vector(&least, self.dimensions),
+            counts,
+            json_array(coordinates)
+        )
+    }
+
+    /// Returns the number of times the cell at `(x, y, z)` was executed.
+    fn count(&self, x: T, y: T, z: T) -> u64 {
+        self.cells.get(&(x, y, z)).map_or(0, |(count, _)| *count)
+    }
+
+    /// Returns the least and the greatest position of the cells that were executed, if any.
+    fn region(&self) -> Option<[(T, T, T); 2]> {
+        let mut positions = self.cells.keys();
+        let first = positions.next()?.clone();
+        Some(
+            positions.fold([first.clone(), first], |[least, greatest], (x, y, z)| {
+                [
+                    (
+                        least.0.min(x.clone()),
+                        least.1.min(y.clone()),
+                        least.2.min(z.clone()),
+                    ),
+                    (
+                        greatest.0.max(x.clone()),
+                        greatest.1.max(y.clone()),
+                        greatest.2.max(z.clone()),
+                    ),
+                ]
+            }),
+        )
+    }
+
+    /// Returns the coordinates that the heat map and the matrices cover along each axis, if any.
+    fn axes(&self) -> Option<[Vec<Option<T>>; 3]> {
+        if self.cells.is_empty() {
+            return None;
+        }
+        let positions = self.cells.keys();
+        Some([
+            axis(positions.clone().map(|(x, _, _)| x).collect()),
+            axis(positions.clone().map(|(_, y, _)| y).collect()),
+            axis(positions.map(|(_, _, z)| z).collect()),
+        ])
+    }
+}
+
+/// Returns the shade of a cell that was executed `count` times, out of at most `max`.
+fn shade(count: u64, max: u64) -> char {
+    if count == 0 {
+        return SHADES[0];
+    }
+    if max <= 1 {
+        return SHADES[SHADES.len() - 1];
+    }
+    let levels = (SHADES.len() - 2) as f64;
+    let level = 1 + (levels * (count as f64).ln() / (max as f64).ln()).round() as usize;
+    SHADES[level.min(SHADES.len() - 1)]
+}
+
+/**
+Returns the coordinates along an axis that the heat map and the matrices cover, in order:
+the `coordinates` of the executed cells and the empty ones between them, except that
+a run of more than `MAX_GAP` empty ones is replaced by a single `None`.
+*/
+fn axis<T: FungeInteger>(coordinates: BTreeSet<&T>) -> Vec<Option<T>> {
+    let mut axis: Vec<Option<T>> = Vec::new();
+    for coordinate in coordinates {
+        if let Some(Some(previous)) = axis.last() {
+            // the difference of the two coordinates may not even fit in a cell
+            let (distance, overflowed) = coordinate.overflowing_sub(previous);
+            if overflowed || distance.to_usize().is_none_or(|d| d > MAX_GAP + 1) {
+                axis.push(None);
+            } else {
+                let mut value = previous.clone() + T::one();
+                while value < *coordinate {
+                    axis.push(Some(value.clone()));
+                    value += T::one();
+                }
+            }
+        }
+        axis.push(Some(coordinate.clone()));
+    }
+    axis
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shade() {
+        assert_eq!(shade(0, 100), ' ');
+        assert_eq!(shade(1, 1), '█');
+        assert_eq!(shade(1, 100), '░');
+        assert_eq!(shade(10, 100), '▓');
+        assert_eq!(shade(100, 100), '█');
+    }
+
+    #[test]
+    fn test_axis() {
+        assert_eq!(
+            axis([0, 2, 20, 21].iter().collect()),
+            vec![Some(0), Some(1), Some(2), None, Some(20), Some(21)]
+        );
+        // the coordinates on the edges of the cells neither overflow nor leave gaps out
+        assert_eq!(
+            axis([i32::MIN, i32::MAX - 2, i32::MAX].iter().collect()),
+            vec![
+                Some(i32::MIN),
+                None,
+                Some(i32::MAX - 2),
+                Some(i32::MAX - 1),
+                Some(i32::MAX)
+            ]
+        );
+    }
+}
@@ -0,0 +1,346 @@
+use std::{
+    collections::{HashMap, HashSet},
+    io::Write,
+    rc::Rc,
+};
+
+use super::{bounds::Bounds, fungetypes::FungeInteger, Dialect, Program};
+
+/// The most instructions a run holds; a longer straight line is split in several runs.
+const MAX_RUN_LENGTH: usize = 1024;
+
+/// A simple instruction i.e., one that only works on the TOSS (or does nothing at all).
+enum Op<T> {
+    /// Push a (decimal or hex) digit
+    Push(T),
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Remainder,
+    Not,
+    Greater,
+    Duplicate,
+    Swap,
+    Discard,
+    /// Do nothing: `z`, a stretch of spaces, a `#` along with the cell it skips, or a direction change
+    Nop,
+}
+
+/**
+A straight line of simple instructions, as an IP at its first cell, with a given delta,
+executes them: along with the cell of every instruction, it holds the position
+and the delta of the IP once it has executed them all.
+*/
+struct Run<T> {
+    ops: Vec<((T, T, T), Op<T>)>,
+    end_position: (T, T, T),
+    end_delta: (T, T, T),
+}
+
+/// The position and the delta of an IP on the first cell of a run
+type RunKey<T> = [(T, T, T); 2];
+
+/**
+The runs of simple instructions that the fast engine has found so far,
+by the position and the delta of the IP at their first cell.
+
+A run is only valid for the cells it read and the bounds it wrapped around,
+so all runs are dropped when either changes.
+*/
+pub(super) struct RunCache<T: FungeInteger> {
+    runs: HashMap<RunKey<T>, Rc<Run<T>>>,
+    /// The cells that the runs read
+    cells: HashSet<(T, T, T)>,
+    /// The number of times Funge-Space grew and shrank, when the runs were found
+    resizes: (u64, u64),
+}
+
+impl<T: FungeInteger> Default for RunCache<T> {
+    fn default() -> Self {
+        Self {
+            runs: HashMap::new(),
+            cells: HashSet::new(),
+            resizes: (0, 0),
+        }
+    }
+}
+
+impl<T: FungeInteger> RunCache<T> {
+    /// Drops all runs, if one of them read the cell on `position`, which is about to be written to.
+    pub fn invalidate(&mut self, position: &(T, T, T)) {
+        if self.cells.contains(position) {
+            self.clear();
+        }
+    }
+
+    fn clear(&mut self) {
+        self.runs.clear();
+        self.cells.clear();
+    }
+}
+
+/// Decodes `c` as a simple instruction of `dialect`, unless it is not one.
+fn decode<T: FungeInteger>(c: char, dialect: Dialect) -> Option<Op<T>> {
+    let befunge_93 = dialect == Dialect::Befunge93;
+    let op = match c {
+        '0'..='9' => Op::Push(T::from(c.to_digit(10)?)?),
+        'a'..='f' if !befunge_93 => Op::Push(T::from(c.to_digit(16)?)?),
+        '+' => Op::Add,
+        '-' => Op::Subtract,
+        '*' => Op::Multiply,
+        // Befunge-93 asks the user for the result of a division by 0
+        '/' if !befunge_93 => Op::Divide,
+        '%' if !befunge_93 => Op::Remainder,
+        '!' => Op::Not,
+        '`' => Op::Greater,
+        ':' => Op::Duplicate,
+        '\\' => Op::Swap,
+        '$' => Op::Discard,
+        'z' if !befunge_93 => Op::Nop,
+        _ => return None,
+    };
+    Some(op)
+}
+
+/// Returns the delta that `c` sets in `dialect`, if it is an instruction that always sets the same one.
+fn direction<T: FungeInteger>(c: char, dialect: Dialect) -> Option<(T, T, T)> {
+    let (zero, one) = (T::zero(), T::one());
+    let dimensions = dialect.dimensions();
+    match c {
+        '>' => Some((one, zero.clone(), zero)),
+        '<' => Some((-one, zero.clone(), zero)),
+        'v' if dimensions > 1 => Some((zero.clone(), one, zero)),
+        '^' if dimensions > 1 => Some((zero.clone(), -one, zero)),
+        'h' if dimensions > 2 => Some((zero.clone(), zero, one)),
+        'l' if dimensions > 2 => Some((zero.clone(), zero, -one)),
+        _ => None,
+    }
+}
+
+impl<T: FungeInteger> Program<T> {
+    /// Returns the value of the cell on `position` as a character, recording that a run read it.
+    fn read_for_run(&self, position: &(T, T, T), cells: &mut Vec<(T, T, T)>) -> char {
+        cells.push(position.clone());
+        let c = self.get_cell(position.clone());
+        char::from_u32(c.to_u32().unwrap_or_default()).unwrap_or_default()
+    }
+
+    /**
+    Finds the run of simple instructions that an IP on `position`, with `delta`, executes next,
+    along with the cells it read to find it; the run is empty if the first instruction is not simple.
+    */
+    fn find_run(
+        &self,
+        mut position: (T, T, T),
+        mut delta: (T, T, T),
+        bounds: &Bounds<T>,
+    ) -> (Run<T>, Vec<(T, T, T)>) {
+        let mut ops = Vec::new();
+        let mut cells = Vec::new();
+        while ops.len() < MAX_RUN_LENGTH {
+            let c = self.read_for_run(&position, &mut cells);
+            let next = match c {
+                // a stretch of spaces is executed in a single tick
+                ' ' => {
+                    let mut next = bounds.step(position.clone(), &delta);
+                    while self.read_for_run(&next, &mut cells) == ' ' {
+                        next = bounds.step(next, &delta);
+                    }
+                    ops.push((position, Op::Nop));
+                    next
+                }
+                '#' => {
+                    let skipped = bounds.step(position.clone(), &delta);
+                    ops.push((position, Op::Nop));
+                    bounds.step(skipped, &delta)
+                }
+                c => match (direction(c, self.dialect), decode(c, self.dialect)) {
+                    // the run ends with a direction change
+                    (Some(new_delta), _) => {
+                        delta = new_delta;
+                        ops.push((position.clone(), Op::Nop));
+                        position = bounds.step(position, &delta);
+                        break;
+                    }
+                    (None, Some(op)) => {
+                        let next = bounds.step(position.clone(), &delta);
+                        ops.push((position, op));
+                        next
+                    }
+                    (None, None) => break,
+                },
+            };
+            position = next;
+        }
+        let run = Run {
+            ops,
+            end_position: position,
+            end_delta: delta,
+        };
+        (run, cells)
+    }
+
+    /// Executes a simple instruction, exactly like `execute_current_cell` does.
+    fn execute_op(&mut self, op: &Op<T>) {
+        macro_rules! push_with_overflow_check {
+            ($a:expr, $op:ident, $b:expr, $message:expr) => {
+                let (res, overflowed) = $a.$op($b);
+                if overflowed {
+                    warning!(self, $message);
+                }
+                self.push(res);
+            };
+        }
+
+        match op {
+            Op::Push(n) => self.push(n.clone()),
+            Op::Add => {
+                let (a, b) = (self.pop(), self.pop());
+                push_with_overflow_check!(
+                    a,
+                    overflowing_add,
+                    &b,
+                    "An addition resulted in overflow."
+                );
+            }
+            Op::Subtract => {
+                let (a, b) = (self.pop(), self.pop());
+                push_with_overflow_check!(
+                    b,
+                    overflowing_sub,
+                    &a,
+                    "A subtraction resulted in overflow."
+                );
+            }
+            Op::Multiply => {
+                let (a, b) = (self.pop(), self.pop());
+                push_with_overflow_check!(
+                    a,
+                    overflowing_mul,
+                    &b,
+                    "A multiplication resulted in overflow."
+                );
+            }
+            Op::Divide => {
+                let (a, b) = (self.pop(), self.pop());
+                if a == T::zero() {
+                    warning!(
+                        self,
+                        "Division by 0 occured. Will return 0 as per the language specification."
+                    );
+                    self.push(T::zero());
+                } else {
+                    push_with_overflow_check!(
+                        b,
+                        overflowing_div,
+                        &a,
+                        "A division resulted in overflow."
+                    );
+                }
+            }
+            Op::Remainder => {
+                let (a, b) = (self.pop(), self.pop());
+                if a == T::zero() {
+                    warning!(self, "Remainder with divisor of 0 occured. Will return 0 as per the language specification.");
+                    self.push(T::zero());
+                } else {
+                    push_with_overflow_check!(
+                        b,
+                        overflowing_rem,
+                        &a,
+                        "A remainder resulted in overflow."
+                    );
+                }
+            }
+            Op::Not => {
+                let a = self.pop();
+                self.push(if a == T::zero() { T::one() } else { T::zero() })
+            }
+            Op::Greater => {
+                let (a, b) = (self.pop(), self.pop());
+                self.push(if b > a { T::one() } else { T::zero() })
+            }
+            Op::Duplicate => {
+                let a = self.pop();
+                self.push(a.clone());
+                self.push(a);
+            }
+            Op::Swap => {
+                let (a, b) = (self.pop(), self.pop());
+                self.push(a);
+                self.push(b);
+            }
+            Op::Discard => {
+                self.pop();
+            }
+            Op::Nop => {}
+        }
+    }
+
+    /**
+    Executes (at most `max_ticks` instructions of) the run of simple instructions
+    that the current IP is on, if the fast engine executes the program, and returns
+    the number of instructions it executed, one per tick.
+
+    Runs are only executed at once while the program has a single IP, in which case
+    every instruction takes a tick of its own, and while nothing needs to watch
+    every instruction (a trace, a profile or a `step`); otherwise, this does nothing.
+    */
+    pub(super) fn execute_run(&mut self, max_ticks: usize) -> usize {
+        if self.runs.is_none()
+            || self.ips.len() != 1
+            || self.ip().string_mode
+            || self.trace.is_some()
+            || self.profile.is_some()
+            || self.written_cells.is_some()
+        {
+            return 0;
+        }
+        let key: RunKey<T> = [
+            self.ip().cursor.position(),
+            self.ip().cursor.delta_members(),
+        ];
+        let resizes = self.grid.resizes();
+        let cached = match &mut self.runs {
+            Some(runs) => {
+                if runs.resizes != resizes {
+                    runs.clear();
+                    runs.resizes = resizes;
+                }
+                runs.runs.get(&key).cloned()
+            }
+            None => None,
+        };
+        let run = match cached {
+            Some(run) => run,
+            None => {
+                let [position, delta] = key.clone();
+                let (run, cells) = self.find_run(position, delta, self.grid.get_bounds());
+                let run = Rc::new(run);
+                if let Some(runs) = &mut self.runs {
+                    runs.cells.extend(cells);
+                    runs.runs.insert(key, run.clone());
+                }
+                run
+            }
+        };
+
+        let n = run.ops.len().min(max_ticks);
+        for (_, op) in &run.ops[..n] {
+            self.execute_op(op);
+        }
+        let cursor = &mut self.ip_mut().cursor;
+        if n == run.ops.len() {
+            let (x, y, z) = run.end_position.clone();
+            cursor.set_position(x, y, z);
+            cursor.set_delta_members(run.end_delta.clone());
+        } else {
+            // the run was cut short, before a direction change
+            let (x, y, z) = run.ops[n].0.clone();
+            cursor.set_position(x, y, z);
+        }
+        self.tick += n as u64;
+        n
+    }
+}
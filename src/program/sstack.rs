@@ -1,6 +1,12 @@
 use super::fungetypes::FungeInteger;
 
-#[derive(Default)]
+/// Returns the absolute value of `n` as a count of elements, if it fits in a `usize`.
+fn magnitude<T: FungeInteger>(n: &T) -> Option<usize> {
+    n.to_i128()
+        .and_then(|n| usize::try_from(n.unsigned_abs()).ok())
+}
+
+#[derive(Default, Clone)]
 pub(super) struct SStack<T> {
     stacks: Vec<Vec<T>>,
 }
@@ -45,18 +51,26 @@ impl<T: FungeInteger> SStack<T> {
         self.get_toss().pop().unwrap_or_default()
     }
 
-    /// Creates a new stack (i.e., a new TOSS).
-    pub fn create_stack(&mut self, n: T, so: (T, T)) {
+    /**
+    Creates a new stack (i.e., a new TOSS), pushing `so`
+    (the components of the current storage offset) onto the old one.
+    Returns `None`, leaving the stacks untouched, if the zeros
+    that a negative `n` asks for do not fit in memory.
+    */
+    pub fn create_stack(&mut self, n: T, so: Vec<T>) -> Option<()> {
         let soss = self.get_toss();
         let soss_len = soss.len();
         let mut toss = vec![];
         match n.cmp(&T::zero()) {
             std::cmp::Ordering::Less => {
-                soss.append(&mut vec![T::zero(); n.abs().to_usize().unwrap()])
+                let count = magnitude(&n)?;
+                soss.try_reserve(count).ok()?;
+                soss.resize(soss_len + count, T::zero());
             }
             std::cmp::Ordering::Equal => {}
             std::cmp::Ordering::Greater => {
-                let n_u = n.to_usize().unwrap();
+                // a count beyond the size of the stack moves all of it
+                let n_u = magnitude(&n).unwrap_or(usize::MAX);
                 if n_u > soss_len {
                     toss.append(soss);
                 } else {
@@ -65,34 +79,47 @@ impl<T: FungeInteger> SStack<T> {
             }
         }
         // step 2: push storage offset as a vector to the old TOSS, now SOSS
-        soss.push(so.0);
-        soss.push(so.1);
+        soss.extend(so);
         // step 3:
         self.stacks.push(toss);
+        Some(())
     }
 
-    pub fn destroy_stack(&mut self, n: T) -> Option<(T, T)> {
+    /**
+    Destroys the TOSS and returns the storage offset (of `dimensions` components)
+    that was stored on the SOSS when it was created.
+    Returns `None`, leaving the stacks untouched, if there is no SOSS
+    or if the zeros that a positive `n` asks for do not fit in memory.
+    */
+    pub fn destroy_stack(&mut self, n: T, dimensions: usize) -> Option<Vec<T>> {
         if self.stacks.len() < 2 {
             None
         } else {
+            if n > T::zero() {
+                let count = magnitude(&n)?;
+                let n_stacks = self.stacks.len();
+                self.stacks[n_stacks - 2].try_reserve(count).ok()?;
+            }
             let mut toss = self.stacks.pop()?;
             let toss_len = toss.len();
             let soss = self.stacks.last_mut()?;
             // step 1: pop (previous) storage offset
-            let (y, x) = (
-                soss.pop().unwrap_or_default(),
-                soss.pop().unwrap_or_default(),
-            );
+            let mut so: Vec<T> = (0..dimensions)
+                .map(|_| soss.pop().unwrap_or_default())
+                .collect();
+            so.reverse();
             let soss_len = soss.len();
 
             // step 2: move n elements from TOSS to SOSS
             match n.cmp(&T::zero()) {
                 std::cmp::Ordering::Less => {
-                    soss.truncate(soss_len - n.abs().to_usize().unwrap());
+                    // a count beyond the size of the stack empties it
+                    let count = magnitude(&n).unwrap_or(usize::MAX);
+                    soss.truncate(soss_len.saturating_sub(count));
                 }
                 std::cmp::Ordering::Equal => {}
                 std::cmp::Ordering::Greater => {
-                    let n_u = n.to_usize().unwrap();
+                    let n_u = magnitude(&n)?;
                     if n_u > toss_len {
                         soss.append(&mut [vec![T::zero(); n_u - toss_len], toss.to_vec()].concat());
                     } else {
@@ -101,7 +128,7 @@ impl<T: FungeInteger> SStack<T> {
                 }
             };
             // step 3: return the previous (and now current) storage offset
-            Some((x, y))
+            Some(so)
         }
     }
 
@@ -114,7 +141,7 @@ impl<T: FungeInteger> SStack<T> {
             } else {
                 self.get_toss()
             };
-            let n = count.abs().to_usize().unwrap_or_default();
+            let n = magnitude(&count).unwrap_or_default();
             if n > 0 {
                 let mut popped = vec![];
                 for _ in 0..n {
@@ -136,6 +163,10 @@ impl<T: FungeInteger> SStack<T> {
         &self.stacks
     }
 
+    pub fn get_stacks_mut(&mut self) -> &mut Vec<Vec<T>> {
+        &mut self.stacks
+    }
+
     /// Returns the `n`th element of the TOSS,
     /// counting from the top (the topmost element has index 0).
     pub fn get(&mut self, n: usize) -> T {
@@ -143,7 +174,7 @@ impl<T: FungeInteger> SStack<T> {
         if n >= toss_len {
             T::zero()
         } else {
-            self.get_toss()[toss_len - n - 1]
+            self.get_toss()[toss_len - n - 1].clone()
         }
     }
 }
@@ -0,0 +1,41 @@
+use super::{fungetypes::FungeInteger, Exit};
+
+/**
+What happened during a single step of a program i.e.,
+during the execution of a single instruction by a single IP.
+*/
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Step<T: FungeInteger> {
+    /// The ID of the IP that executed the instruction
+    pub ip_id: usize,
+    /// The instruction (the value of the cell) that was executed
+    pub instruction: T,
+    /// Whether the instruction was executed in string mode i.e., just pushed
+    pub string_mode: bool,
+    /// The position of the IP before the step, where the instruction lies
+    pub position_before: (T, T, T),
+    /// The delta of the IP before the step
+    pub delta_before: (T, T, T),
+    /// The position of the IP after the step, unless the IP ended
+    pub position_after: Option<(T, T, T)>,
+    /// The delta of the IP after the step, unless the IP ended
+    pub delta_after: Option<(T, T, T)>,
+    /**
+    The values that were removed from the top of the TOSS, topmost last;
+    a value that was popped from an empty stack (as 0) is not among them.
+    */
+    pub popped: Vec<T>,
+    /// The values that were added on top of the TOSS, topmost last
+    pub pushed: Vec<T>,
+    /// The positions of the cells that were written to (by `p`, `s`, `i` or a fingerprint)
+    pub written: Vec<(T, T, T)>,
+    /// The way the program terminated, if it did during this step
+    pub exit: Option<Exit>,
+}
+
+impl<T: FungeInteger> Step<T> {
+    /// Returns the instruction that was executed as a character, if it is one.
+    pub fn instruction_char(&self) -> Option<char> {
+        self.instruction.to_u32().and_then(char::from_u32)
+    }
+}
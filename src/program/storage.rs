@@ -0,0 +1,19 @@
+/**
+The way Funge-Space is stored.
+
+A dense Funge-Space is a box of cells that is as large as the bounds of Funge-Space,
+which is the fastest to read from and write to, as long as the cells of the program are
+close to one another. A sparse Funge-Space only stores fixed-size chunks of cells around
+the cells that were written to, so cells that lie far apart take no more memory or time
+to write to than neighbouring ones do.
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Storage {
+    /// Dense, until a cell is written so far away that the box of cells would get huge
+    #[default]
+    Auto,
+    /// Always dense
+    Dense,
+    /// Always sparse
+    Sparse,
+}
@@ -0,0 +1,66 @@
+use std::io::{self, Read, Write};
+
+/**
+The standard streams of a program: where its input is read from
+and where its output and its warnings are written to.
+
+By default, these are the standard streams of the process.
+*/
+pub(super) struct Streams {
+    pub stdin: Box<dyn Read>,
+    pub stdout: Box<dyn Write>,
+    pub stderr: Box<dyn Write>,
+}
+
+impl Default for Streams {
+    fn default() -> Self {
+        Self {
+            stdin: Box::new(io::stdin()),
+            stdout: Box::new(io::stdout()),
+            stderr: Box::new(io::stderr()),
+        }
+    }
+}
+
+impl Streams {
+    /// Reads a single byte from stdin, or returns `None` at the end of input.
+    pub fn read_byte(&mut self) -> io::Result<Option<u8>> {
+        let mut byte = [0];
+        loop {
+            match self.stdin.read(&mut byte) {
+                Ok(0) => return Ok(None),
+                Ok(_) => return Ok(Some(byte[0])),
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /**
+    Reads a line (including its `\n`, if there is one) from stdin,
+    or returns `None` at the end of input.
+
+    The line is read byte by byte, so no input beyond it is consumed;
+    the same stdin may be read by something else afterwards.
+    */
+    pub fn read_line(&mut self) -> io::Result<Option<String>> {
+        let mut line = Vec::new();
+        while let Some(b) = self.read_byte()? {
+            line.push(b);
+            if b == b'\n' {
+                break;
+            }
+        }
+        if line.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(String::from_utf8_lossy(&line).into_owned()))
+        }
+    }
+
+    /// Writes `s` to stdout and flushes it, so that it is shown right away.
+    pub fn print(&mut self, s: &str) -> io::Result<()> {
+        self.stdout.write_all(s.as_bytes())?;
+        self.stdout.flush()
+    }
+}
@@ -1,14 +1,17 @@
-use chrono::{Datelike, Timelike, Utc as time};
-use std::{env, mem::size_of};
+use chrono::{Datelike, Timelike};
+use std::env;
 
-use super::{fungetypes::FungeInteger, Program};
+use super::{fungetypes::FungeInteger, Paradigm, Program};
+
+/// The number of items of the report of `y`.
+const REPORT_ITEMS: usize = 20;
 
 pub trait SystemInfoReporter<T: FungeInteger> {
-    fn get_env_flags() -> Vec<T>;
+    fn get_env_flags(&self) -> Vec<T>;
     fn get_cell_size() -> Vec<T>;
     fn get_handprint() -> Vec<T>;
     fn get_version() -> Vec<T>;
-    fn get_operating_paradigm() -> Vec<T>;
+    fn get_operating_paradigm(&self) -> Vec<T>;
     fn get_path_separator() -> Vec<T>;
     fn get_dimensions(&self) -> Vec<T>;
     fn get_ip_id(&self) -> Vec<T>;
@@ -18,23 +21,25 @@ pub trait SystemInfoReporter<T: FungeInteger> {
     fn get_storage_offset(&self) -> Vec<T>;
     fn get_least_grid_point(&self) -> Vec<T>;
     fn get_greatest_grid_point(&self) -> Vec<T>;
-    fn get_day() -> Vec<T>;
-    fn get_second() -> Vec<T>;
+    fn get_day(&self) -> Vec<T>;
+    fn get_second(&self) -> Vec<T>;
     fn get_sstack_size(&self) -> Vec<T>;
     fn get_stack_sizes(&self) -> Vec<T>;
     fn get_cli_args() -> Vec<T>;
     fn get_env_vars() -> Vec<T>;
 
+    fn get_report_item(&self, n: usize) -> Vec<T>;
     fn get_full_report(&self) -> Vec<T>;
+    fn get_report_cell(&self, n: usize) -> Result<T, usize>;
 }
 
 impl<T: FungeInteger> SystemInfoReporter<T> for Program<T> {
     // 1
-    fn get_env_flags() -> Vec<T> {
-        let t = 0;
-        let i = 0;
-        let o = 0;
-        let e = 0;
+    fn get_env_flags(&self) -> Vec<T> {
+        let t = 1;
+        let i = 1;
+        let o = 1;
+        let e = u8::from(self.paradigm != Paradigm::Disabled);
         let unbuffered = 1;
         let flags: u8 = t + (i << 1) + (o << 2) + (e << 3) + (unbuffered << 4);
         vec![T::from(flags).unwrap_or_default()]
@@ -42,7 +47,7 @@ impl<T: FungeInteger> SystemInfoReporter<T> for Program<T> {
 
     // 2
     fn get_cell_size() -> Vec<T> {
-        vec![T::from(size_of::<T>()).unwrap_or_default()]
+        vec![T::from(T::SIZE).unwrap_or_default()]
     }
 
     // 3
@@ -56,9 +61,8 @@ impl<T: FungeInteger> SystemInfoReporter<T> for Program<T> {
         vec![T::from(version.parse::<i32>().unwrap()).unwrap_or_default()]
     }
     // 5
-    fn get_operating_paradigm() -> Vec<T> {
-        // TODO: fix this after implementing the '=' instruction
-        vec![T::zero()]
+    fn get_operating_paradigm(&self) -> Vec<T> {
+        vec![T::from(self.paradigm.id()).unwrap_or_default()]
     }
 
     // 6
@@ -68,53 +72,47 @@ impl<T: FungeInteger> SystemInfoReporter<T> for Program<T> {
 
     // 7
     fn get_dimensions(&self) -> Vec<T> {
-        // TODO: change that when Unefunge restrictions are implemented for the REPL
-        vec![T::from(self.get_position().len()).unwrap_or_default()]
+        vec![T::from(self.dialect.dimensions()).unwrap_or_default()]
     }
 
     // 8
     fn get_ip_id(&self) -> Vec<T> {
-        // TODO: implement that in concurrency
-        vec![T::zero()]
+        vec![T::from(self.ip().id).unwrap_or_default()]
     }
 
     // 9
     fn get_team_number(&self) -> Vec<T> {
-        // TODO: implement something for this?
-        vec![T::zero()]
+        vec![T::from(self.ip().team).unwrap_or_default()]
     }
 
     // 10
     fn get_position(&self) -> Vec<T> {
-        let position = self.cursor.position();
-        vec![position.0, position.1]
+        self.vector_components(self.ip().cursor.position())
     }
 
     // 11
     fn get_delta(&self) -> Vec<T> {
-        let delta = self.cursor.delta();
-        vec![delta.x, delta.y]
+        self.vector_components(self.ip().cursor.delta_members())
     }
 
     // 12
     fn get_storage_offset(&self) -> Vec<T> {
-        let so = self.cursor.storage_offset();
-        vec![so.0, so.1]
+        self.vector_components(self.ip().cursor.storage_offset())
     }
 
     // 13
     fn get_least_grid_point(&self) -> Vec<T> {
-        self.grid.get_least_point()
+        self.vector_components(self.grid.get_least_point())
     }
 
     // 14
     fn get_greatest_grid_point(&self) -> Vec<T> {
-        self.grid.get_greatest_point()
+        self.vector_components(self.grid.get_greatest_point())
     }
 
     // 15
-    fn get_day() -> Vec<T> {
-        let current_time = time::now();
+    fn get_day(&self) -> Vec<T> {
+        let current_time = self.clock.now();
         let year = current_time.year() - 1900;
         let month = current_time.month() as i32;
         let day = current_time.day() as i32;
@@ -122,8 +120,8 @@ impl<T: FungeInteger> SystemInfoReporter<T> for Program<T> {
     }
 
     // 16
-    fn get_second() -> Vec<T> {
-        let current_time = time::now();
+    fn get_second(&self) -> Vec<T> {
+        let current_time = self.clock.now();
         let hour = current_time.hour();
         let minute = current_time.minute();
         let second = current_time.second();
@@ -132,12 +130,13 @@ impl<T: FungeInteger> SystemInfoReporter<T> for Program<T> {
 
     // 17
     fn get_sstack_size(&self) -> Vec<T> {
-        vec![T::from(self.sstack.get_stacks().len()).unwrap_or_default()]
+        vec![T::from(self.ip().sstack.get_stacks().len()).unwrap_or_default()]
     }
 
     // 18
     fn get_stack_sizes(&self) -> Vec<T> {
-        self.sstack
+        self.ip()
+            .sstack
             .get_stacks()
             .iter()
             .map(|v| T::from(v.len()).unwrap_or_default())
@@ -184,29 +183,55 @@ impl<T: FungeInteger> SystemInfoReporter<T> for Program<T> {
         res.into_iter().rev().collect()
     }
 
+    /// Returns item `n` of the report (1 to 20), in the order its cells are pushed.
+    fn get_report_item(&self, n: usize) -> Vec<T> {
+        match n {
+            1 => self.get_env_flags(),
+            2 => Self::get_cell_size(),
+            3 => Self::get_handprint(),
+            4 => Self::get_version(),
+            5 => self.get_operating_paradigm(),
+            6 => Self::get_path_separator(),
+            7 => self.get_dimensions(),
+            8 => self.get_ip_id(),
+            9 => self.get_team_number(),
+            10 => self.get_position(),
+            11 => self.get_delta(),
+            12 => self.get_storage_offset(),
+            13 => self.get_least_grid_point(),
+            14 => self.get_greatest_grid_point(),
+            15 => self.get_day(),
+            16 => self.get_second(),
+            17 => self.get_sstack_size(),
+            18 => self.get_stack_sizes(),
+            19 => Self::get_cli_args(),
+            20 => Self::get_env_vars(),
+            _ => vec![],
+        }
+    }
+
     fn get_full_report(&self) -> Vec<T> {
-        vec![
-            Self::get_env_vars(),
-            Self::get_cli_args(),
-            self.get_stack_sizes(),
-            self.get_sstack_size(),
-            Self::get_second(),
-            Self::get_day(),
-            self.get_greatest_grid_point(),
-            self.get_least_grid_point(),
-            self.get_storage_offset(),
-            self.get_delta(),
-            self.get_position(),
-            self.get_team_number(),
-            self.get_ip_id(),
-            self.get_dimensions(),
-            Self::get_path_separator(),
-            Self::get_operating_paradigm(),
-            Self::get_version(),
-            Self::get_handprint(),
-            Self::get_cell_size(),
-            Self::get_env_flags(),
-        ]
-        .concat()
+        (1..=REPORT_ITEMS)
+            .rev()
+            .flat_map(|n| self.get_report_item(n))
+            .collect()
+    }
+
+    /**
+    Returns cell `n` of the report, counting from its top (starting at 1),
+    computing only the items up to the one that holds it; or, if the report
+    is shorter than that, the number of cells of the whole report.
+    */
+    fn get_report_cell(&self, mut n: usize) -> Result<T, usize> {
+        let mut length = 0;
+        for item in 1..=REPORT_ITEMS {
+            let cells = self.get_report_item(item);
+            if n <= cells.len() {
+                return Ok(cells[cells.len() - n].clone());
+            }
+            n -= cells.len();
+            length += cells.len();
+        }
+        Err(length)
     }
 }
@@ -0,0 +1,154 @@
+use std::{
+    fmt::Write as _,
+    io::{self, Write},
+};
+
+use super::{format::json_vector, fungetypes::FungeInteger, IpState};
+
+/**
+A trace of the execution of a program: a JSON object on a line of its own (JSON Lines)
+for every instruction that an IP executes, which describes the IP right before it does.
+
+The objects always have the following members, in this order:
+
+- `tick`: the number of the tick (starting at 0) in which the instruction is executed;
+  in every tick, every IP executes a single instruction
+- `ip`: the ID of the IP
+- `position`: the position of the IP i.e., of the instruction
+- `delta`: the delta of the IP
+- `offset`: the storage offset of the IP
+- `instruction`: the instruction as a string of a single character,
+  or `null` if the value of the cell is not a character
+- `value`: the value of the cell of the instruction
+- `string_mode`: whether the IP is in string mode, so the instruction is just pushed
+- `toss`: (at most) the topmost values of the TOSS, topmost first
+- `toss_size`: the number of values on the TOSS
+- `stacks`: the number of stacks on the stack stack
+
+Vectors are arrays of one, two or three numbers, as many as the dimensions of the program.
+For example, the first record of a Befunge program that begins with `1` is:
+
+```json
+{"tick":0,"ip":0,"position":[0,0],"delta":[1,0],"offset":[0,0],"instruction":"1","value":49,"string_mode":false,"toss":[],"toss_size":0,"stacks":1}
+```
+*/
+pub struct Trace<T: FungeInteger> {
+    output: Box<dyn Write>,
+    top: usize,
+    /// The least and the greatest position of the traced region
+    region: Option<[(T, T, T); 2]>,
+    instructions: Option<Vec<char>>,
+}
+
+impl<T: FungeInteger> Trace<T> {
+    /// The number of values of the TOSS that are traced, unless told otherwise.
+    pub const DEFAULT_TOP: usize = 5;
+
+    /// Starts a trace that is written to `output`, which should better be buffered.
+    pub fn new(output: impl Write + 'static) -> Self {
+        Self {
+            output: Box::new(output),
+            top: Self::DEFAULT_TOP,
+            region: None,
+            instructions: None,
+        }
+    }
+
+    /// Sets the number of values of the TOSS that are traced (at most).
+    pub fn top(mut self, top: usize) -> Self {
+        self.top = top;
+        self
+    }
+
+    /// Traces only the instructions in the region from `from` to `to`, both inclusive.
+    pub fn region(mut self, from: (T, T, T), to: (T, T, T)) -> Self {
+        self.region = Some([from, to]);
+        self
+    }
+
+    /// Traces only the given `instructions`, either in or out of string mode.
+    pub fn instructions(mut self, instructions: &str) -> Self {
+        self.instructions = Some(instructions.chars().collect());
+        self
+    }
+
+    /// Writes the record of the instruction `value` that `ip` is about to execute, unless it is filtered out.
+    pub(super) fn record(
+        &mut self,
+        tick: u64,
+        ip: IpState<'_, T>,
+        value: &T,
+        dimensions: usize,
+    ) -> io::Result<()> {
+        let position = ip.position();
+        let instruction = value.to_u32().and_then(char::from_u32);
+        if let Some([from, to]) = &self.region {
+            let within = |p: &T, from: &T, to: &T| from <= p && p <= to;
+            if !within(&position.0, &from.0, &to.0)
+                || !within(&position.1, &from.1, &to.1)
+                || !within(&position.2, &from.2, &to.2)
+            {
+                return Ok(());
+            }
+        }
+        if let Some(instructions) = &self.instructions {
+            if !instruction.is_some_and(|c| instructions.contains(&c)) {
+                return Ok(());
+            }
+        }
+
+        let toss = ip.toss();
+        let top: Vec<String> = toss
+            .iter()
+            .rev()
+            .take(self.top)
+            .map(|value| value.to_string())
+            .collect();
+        writeln!(
+            self.output,
+            "{{\"tick\":{},\"ip\":{},\"position\":{},\"delta\":{},\"offset\":{},\
+             \"instruction\":{},\"value\":{},\"string_mode\":{},\"toss\":[{}],\
+             \"toss_size\":{},\"stacks\":{}}}",
+            tick,
+            ip.id(),
+            json_vector(&position, dimensions),
+            json_vector(&ip.delta(), dimensions),
+            json_vector(&ip.storage_offset(), dimensions),
+            instruction.map_or_else(|| "null".to_string(), json_string),
+            value,
+            ip.string_mode(),
+            top.join(","),
+            toss.len(),
+            // an IP that has not pushed anything yet still has its TOSS
+            ip.stacks().len().max(1)
+        )
+    }
+}
+
+/// Formats a character as a JSON string.
+fn json_string(c: char) -> String {
+    let mut s = String::from('"');
+    match c {
+        '"' => s.push_str("\\\""),
+        '\\' => s.push_str("\\\\"),
+        // unwrap is safe here, writing to a String never fails
+        c if c.is_control() => write!(s, "\\u{:04x}", c as u32).unwrap(),
+        c => s.push(c),
+    }
+    s.push('"');
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_string() {
+        assert_eq!(json_string('a'), "\"a\"");
+        assert_eq!(json_string('"'), "\"\\\"\"");
+        assert_eq!(json_string('\\'), "\"\\\\\"");
+        assert_eq!(json_string('\n'), "\"\\u000a\"");
+        assert_eq!(json_string('λ'), "\"λ\"");
+    }
+}
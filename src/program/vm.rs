@@ -1,31 +1,75 @@
-use super::{delta::Delta, fungetypes::FungeInteger, sysinfo::SystemInfoReporter, Program};
+use super::{
+    delta::Delta, fungetypes::FungeInteger, streams::Streams, sysinfo::SystemInfoReporter, Dialect,
+    Exit, IpState, Program, Step,
+};
 use anyhow::{bail, Context, Result};
-use colour::e_yellow;
-use std::io::{self, Read, Write};
+use std::{io::Write, time::Instant};
 
-macro_rules! warning {
-    ($message:expr) => {
-        e_yellow!("warning: ");
-        eprintln!($message);
-    };
-}
+/// The instructions Befunge-93 defines; every other one reflects.
+pub(super) const BEFUNGE_93_INSTRUCTIONS: &str = "0123456789+-*/%!`><^v?_|\":\\$.,#pg&~@ ";
 
 impl<T: FungeInteger> Program<T> {
     /// Pushes `x` into the program stack.
-    fn push(&mut self, x: T) {
-        self.sstack.push_onto_toss(x);
+    pub(super) fn push(&mut self, x: T) {
+        self.ip_mut().sstack.push_onto_toss(x);
     }
 
     /**
     Pops and returns a `T` from the program stack.
     */
-    fn pop(&mut self) -> T {
-        self.sstack.pop_from_toss()
+    pub(super) fn pop(&mut self) -> T {
+        self.ip_mut().sstack.pop_from_toss()
+    }
+
+    /// Returns the components of the vector `v` that exist in the dialect of the program.
+    pub(super) fn vector_components(&self, (x, y, z): (T, T, T)) -> Vec<T> {
+        let mut components = vec![x, y, z];
+        components.truncate(self.dialect.dimensions());
+        components
+    }
+
+    /// Pushes the vector `v` into the program stack, one component per dimension.
+    pub(super) fn push_vector(&mut self, v: (T, T, T)) {
+        for component in self.vector_components(v) {
+            self.push(component);
+        }
+    }
+
+    /**
+    Pops and returns a vector from the program stack, one component per dimension;
+    the components beyond the dimensions of the program are 0.
+    */
+    pub(super) fn pop_vector(&mut self) -> (T, T, T) {
+        let dimensions = self.dialect.dimensions();
+        let mut components: Vec<T> = (0..dimensions).map(|_| self.pop()).collect();
+        components.reverse();
+        vector_from_components(components)
+    }
+
+    /// Pops a null-terminated `0"gnirts` from the program stack and returns it.
+    pub(super) fn pop_gnirts(&mut self) -> String {
+        let mut s = String::new();
+        loop {
+            let c = self.pop();
+            if c == T::zero() {
+                break;
+            }
+            s.push(char::from_u32(c.to_u32().unwrap_or_default()).unwrap_or_default());
+        }
+        s
+    }
+
+    /// Pushes `s` into the program stack as a null-terminated `0"gnirts`.
+    pub(super) fn push_gnirts(&mut self, s: &str) {
+        self.push(T::zero());
+        for c in s.chars().rev() {
+            self.push(T::from(c as u32).unwrap_or_default());
+        }
     }
 
     /// A wrapper around the `clear_toss` method of the `sstack` struct.
     fn clear_toss(&mut self) {
-        self.sstack.clear_toss();
+        self.ip_mut().sstack.clear_toss();
     }
 
     /**
@@ -34,11 +78,11 @@ impl<T: FungeInteger> Program<T> {
     If `position` is out of bounds, returns ` ` (space), without annoying
     the underlying `grid` struct.
     */
-    fn get_cell(&self, position: (T, T)) -> T {
-        if self.grid.out_of_bounds(position) {
+    pub(super) fn get_cell(&self, position: (T, T, T)) -> T {
+        if self.grid.out_of_bounds(position.clone()) {
             T::from(32).unwrap() // space
         } else {
-            self.grid[position]
+            self.grid.get(position)
         }
     }
 
@@ -46,11 +90,31 @@ impl<T: FungeInteger> Program<T> {
     Puts `c` on the `position` coordinates of the program grid.
 
     The underlying `grid` struct will resize the Funge-Space in case
-    `position` is out of bounds.
+    `position` is out of bounds, unless its size is fixed (as in Befunge-93),
+    in which case `c` is simply dropped.
     */
-    fn put_cell(&mut self, position: (T, T), c: T) {
-        self.grid[position] = c;
-        if c == T::from(32).unwrap() {
+    pub(super) fn put_cell(&mut self, position: (T, T, T), c: T) {
+        let fixed_size = self.dialect.fixed_size().is_some();
+        if fixed_size && self.grid.out_of_bounds(position.clone()) {
+            warning!(
+                self,
+                "A cell was put outside of the fixed Funge-Space and was dropped."
+            );
+            return;
+        }
+        if let Some(written_cells) = &mut self.written_cells {
+            written_cells.push(position.clone());
+        }
+        if let Some(runs) = &mut self.runs {
+            runs.invalidate(&position);
+        }
+        if fixed_size {
+            self.grid.set(position, c);
+            return;
+        }
+        let is_space = c == T::from(32).unwrap();
+        self.grid.set(position.clone(), c);
+        if is_space {
             self.grid.shrink(position);
         }
     }
@@ -60,124 +124,148 @@ impl<T: FungeInteger> Program<T> {
     pushing each character's ASCII value all the way up to the next `"`.
     */
     fn toggle_string_mode(&mut self) {
-        self.string_mode = !self.string_mode;
+        let ip = self.ip_mut();
+        ip.string_mode = !ip.string_mode;
     }
 
     /**
     A wrapper around the `move` method of the cursor object.
     */
     fn move_cursor(&mut self) {
-        self.cursor.r#move(self.grid.get_bounds());
+        self.ips[self.current_ip]
+            .cursor
+            .r#move(self.grid.get_bounds());
+    }
+
+    /// Returns the value of the cell on `position` as a character, or `'\0'` if it is not one.
+    fn get_char(&self, position: (T, T, T)) -> char {
+        char::from_u32(self.get_cell(position).to_u32().unwrap_or_default()).unwrap_or_default()
     }
 
     /**
     Returns the next instruction at the direction of `delta`,
     respecting the wraparound rules.
-    It ignores the ` ` and `;` instructions.
+    It ignores the ` ` and `;` instructions (and whatever lies between two `;`),
+    without moving the cursor.
     */
-    fn peek(&mut self) -> char {
-        let mut times_moved = 0;
-        let mut current_cell = ' ';
-
-        // find next useful instruction
-        while [' ', ';'].contains(&current_cell) {
-            // skip `;` blocks
-            if current_cell == ';' {
-                loop {
-                    self.move_cursor();
-                    times_moved += 1;
-                    current_cell = char::from_u32(
-                        self.get_cell(self.cursor.position())
-                            .to_u32()
-                            .unwrap_or_default(),
-                    )
-                    .unwrap_or_default();
-                    if current_cell == ';' {
-                        break;
-                    }
-                }
+    fn peek(&self) -> char {
+        let delta = self.ip().cursor.delta_members();
+        let bounds = self.grid.get_bounds();
+        let mut position = self.ip().cursor.position();
+        let mut jumping = false;
+        loop {
+            position = bounds.step(position, &delta);
+            match self.get_char(position.clone()) {
+                ';' => jumping = !jumping,
+                c if c != ' ' && !jumping => return c,
+                _ => {}
             }
-            self.move_cursor();
-            times_moved += 1;
-            current_cell = char::from_u32(
-                self.get_cell(self.cursor.position())
-                    .to_u32()
-                    .unwrap_or_default(),
-            )
-            .unwrap_or_default();
         }
+    }
 
-        // reset cursor
-        self.cursor.reflect();
-        for _ in 0..times_moved {
-            self.move_cursor();
+    /// Adds the time since `started` to the time the program spent waiting for input, if it is profiled.
+    fn blocked_on_input(&mut self, started: Instant) {
+        if let Some(profile) = &mut self.profile {
+            profile.input_time += started.elapsed();
         }
-        self.cursor.reflect();
-
-        current_cell
     }
 
-    /// Skips all consecutive spaces by moving the `cursor` appropriately.
+    /**
+    Skips all consecutive spaces by moving the `cursor` onto the last of them,
+    since it will move again at the end of the instruction.
+    */
     fn skip_spaces(&mut self) {
+        let delta = self.ip().cursor.delta_members();
+        let bounds = self.grid.get_bounds();
+        let mut position = self.ip().cursor.position();
         loop {
-            self.move_cursor();
-            let new_x = self.get_cell(self.cursor.position());
-            if char::from_u32(new_x.to_u32().unwrap_or_default()).unwrap_or_default() != ' ' {
-                // get cursor one space back
-                // because it will move again at the end of the method
-                self.cursor.reflect();
-                self.move_cursor();
-                self.cursor.reflect();
+            let next = bounds.step(position.clone(), &delta);
+            if self.get_char(next.clone()) != ' ' {
                 break;
             }
+            position = next;
         }
+        let (x, y, z) = position;
+        self.ip_mut().cursor.set_position(x, y, z);
     }
 
+    /**
+    Pops a count and then that many cells, and returns the fingerprint ID they form
+    i.e., the popped cells in base 256 (most significant first).
+    */
     fn build_fingerprint(&mut self) -> T {
         let count = self.pop();
         let mut fp = T::zero();
         let a = T::from(256).unwrap_or_default();
         for _ in 0..count.to_usize().unwrap_or_default() {
-            fp *= a;
-            fp += self.pop();
+            // an ID of more than size_of::<T>() cells can not be valid anyway
+            fp = fp.overflowing_mul(&a).0.overflowing_add(&self.pop()).0;
         }
         fp
     }
 
+    /**
+    Executes `instruction` (one of `A`-`Z`) according to the semantics
+    that are currently loaded for it, or reflects if there are none.
+    */
+    fn execute_fingerprint_instruction(&mut self, instruction: char) -> Result<()> {
+        match self.ip().semantics(instruction) {
+            Some(fingerprint) => fingerprint.execute(instruction, self),
+            None => {
+                self.ip_mut().cursor.reflect();
+                Ok(())
+            }
+        }
+    }
+
     /**
     Executes the cell on which the `cursor` lies.
     For the full Befunge 93 instruction list see
     [here](https://en.wikipedia.org/wiki/Befunge#Befunge-93_instruction_list).
 
-    Returns `true` if the `@` cell was executed
-    i.e., the program terminated, and `false` otherwise.
+    Once the cell is executed, the next IP in the list becomes the current one;
+    an IP that executed `@` is removed from the list altogether.
+
+    Returns the way the program terminated, if it did i.e., if the last IP executed `@`
+    or any IP executed `q`, and `None` otherwise.
     */
-    fn execute_current_cell(&mut self) -> Result<(bool, i32)> {
+    pub(super) fn execute_current_cell(&mut self) -> Result<Option<Exit>> {
         // define a helper macro for overflow checks
         macro_rules! push_with_overflow_check {
             ($a:expr, $op:ident, $b:expr, $message:expr) => {
                 let (res, overflowed) = $a.$op($b);
                 if overflowed {
-                    warning!($message);
+                    warning!(self, $message);
                 }
                 self.push(res);
             };
         }
 
-        let position = self.cursor.position();
+        let position = self.ip().cursor.position();
         let mut k = T::one();
-        let mut program_terminated = false;
-        let mut exit_code = 0;
+        let mut exit = None;
+        let mut ip_terminated = false;
 
-        let x = self.get_cell(position);
+        let x = self.get_cell(position.clone());
+
+        if let Some(trace) = &mut self.trace {
+            let ip = IpState(&self.ips[self.current_ip]);
+            trace
+                .record(self.tick, ip, &x, self.dialect.dimensions())
+                .context("Failed to write the trace")?;
+        }
+        if let Some(profile) = &mut self.profile {
+            profile.record(position.clone(), x.clone());
+        }
 
         // special case: string mode ON
-        if self.string_mode {
+        if self.ip().string_mode {
             match char::from_u32(x.to_u32().unwrap_or_default()) {
                 Some('"') => {
                     self.toggle_string_mode();
                 }
-                Some(' ') => {
+                // [SPEC] in Funge-98, consecutive spaces are pushed as a single one
+                Some(' ') if self.dialect != Dialect::Befunge93 => {
                     self.push(x);
                     self.skip_spaces();
                 }
@@ -186,8 +274,14 @@ impl<T: FungeInteger> Program<T> {
         } else if let Some(mut xchar) = char::from_u32(x.to_u32().unwrap_or_default()) {
             while k > T::zero() {
                 match xchar {
+                    // Instructions that Befunge-93 does not define reflect, like unknown ones do
+                    c if self.dialect == Dialect::Befunge93
+                        && !BEFUNGE_93_INSTRUCTIONS.contains(c) =>
+                    {
+                        self.ip_mut().cursor.reflect()
+                    }
                     // Push this decimal number on the stack
-                    d if ('0'..='9').contains(&d) => {
+                    d if d.is_ascii_digit() => {
                         // these unwraps can't fail; we are sure we have a digit
                         self.push(T::from(d.to_digit(10).unwrap()).unwrap());
                     }
@@ -227,26 +321,43 @@ impl<T: FungeInteger> Program<T> {
                         );
                     }
                     /*
-                    Integer division: Pop a and b, then push b/a, rounded towards 0.
-                    [SPEC] division by 0 returns 0
+                    Integer division: Pop a and b, then push b/a, rounded towards 0
+                    (wrapping around for the least cell divided by -1).
+                    [SPEC] division by 0 returns 0; in Befunge-93, it asks the user for the result
                     */
                     '/' => {
                         let (a, b) = (self.pop(), self.pop());
-                        if a == T::zero() {
-                            warning!("Division by 0 occured. Will return 0 as per the language specification.");
+                        if a == T::zero() && self.dialect == Dialect::Befunge93 {
+                            let c = ask_for_result(&mut self.streams, &format!("{}/0", b))?;
+                            self.push(c);
+                        } else if a == T::zero() {
+                            warning!(self, "Division by 0 occured. Will return 0 as per the language specification.");
                             self.push(T::zero());
                         } else {
-                            self.push(b / a);
+                            push_with_overflow_check!(
+                                b,
+                                overflowing_div,
+                                &a,
+                                "A division resulted in overflow."
+                            );
                         }
                     }
                     // Modulo: Pop a and b, then push the remainder of the integer division of b/a.
                     '%' => {
                         let (a, b) = (self.pop(), self.pop());
-                        if a == T::zero() {
-                            warning!("Remainder with divisor of 0 occured. Will return 0 as per the language specification.");
+                        if a == T::zero() && self.dialect == Dialect::Befunge93 {
+                            let c = ask_for_result(&mut self.streams, &format!("{}%0", b))?;
+                            self.push(c);
+                        } else if a == T::zero() {
+                            warning!(self, "Remainder with divisor of 0 occured. Will return 0 as per the language specification.");
                             self.push(T::zero());
                         } else {
-                            self.push(b % a);
+                            push_with_overflow_check!(
+                                b,
+                                overflowing_rem,
+                                &a,
+                                "A remainder resulted in overflow."
+                            );
                         }
                     }
                     // Logical NOT: Pop a value. If the value is zero, push 1; otherwise, push zero.
@@ -259,20 +370,27 @@ impl<T: FungeInteger> Program<T> {
                         let (a, b) = (self.pop(), self.pop());
                         self.push(if b > a { T::one() } else { T::zero() })
                     }
+                    // Instructions that need a second dimension do not exist in Unefunge and reflect
+                    '^' | 'v' | '|' | '[' | ']' | 'w' if self.dialect == Dialect::Unefunge => {
+                        self.ip_mut().cursor.reflect()
+                    }
                     // Start moving right
-                    '>' => self.cursor.set_delta(Delta::east()),
+                    '>' => self.ip_mut().cursor.set_delta(Delta::east()),
                     // Start moving left
-                    '<' => self.cursor.set_delta(Delta::west()),
+                    '<' => self.ip_mut().cursor.set_delta(Delta::west()),
                     // Start moving up
-                    '^' => self.cursor.set_delta(Delta::north()),
+                    '^' => self.ip_mut().cursor.set_delta(Delta::north()),
                     // Start moving down
-                    'v' => self.cursor.set_delta(Delta::south()),
+                    'v' => self.ip_mut().cursor.set_delta(Delta::south()),
                     // Start moving in a random cardinal direction
-                    '?' => self.cursor.set_delta(rand::random()),
+                    '?' => {
+                        let delta = Delta::random(self.dialect.dimensions());
+                        self.ip_mut().cursor.set_delta(delta)
+                    }
                     // Pop a value; move right if value=0, left otherwise
                     '_' => {
                         let a = self.pop();
-                        self.cursor.set_delta(if a == T::zero() {
+                        self.ip_mut().cursor.set_delta(if a == T::zero() {
                             Delta::east()
                         } else {
                             Delta::west()
@@ -281,18 +399,35 @@ impl<T: FungeInteger> Program<T> {
                     // Pop a value; move down if value=0, up otherwise
                     '|' => {
                         let a = self.pop();
-                        self.cursor.set_delta(if a == T::zero() {
+                        self.ip_mut().cursor.set_delta(if a == T::zero() {
                             Delta::south()
                         } else {
                             Delta::north()
                         })
                     }
+                    // Start moving high (Trefunge only)
+                    'h' if self.dialect == Dialect::Trefunge => {
+                        self.ip_mut().cursor.set_delta(Delta::high())
+                    }
+                    // Start moving low (Trefunge only)
+                    'l' if self.dialect == Dialect::Trefunge => {
+                        self.ip_mut().cursor.set_delta(Delta::low())
+                    }
+                    // Pop a value; move low if value=0, high otherwise (Trefunge only)
+                    'm' if self.dialect == Dialect::Trefunge => {
+                        let a = self.pop();
+                        self.ip_mut().cursor.set_delta(if a == T::zero() {
+                            Delta::low()
+                        } else {
+                            Delta::high()
+                        })
+                    }
                     // Start string mode: push each character's ASCII value all the way up to the next "
                     '"' => self.toggle_string_mode(),
                     // Duplicate value on top of the stack
                     ':' => {
                         let a = self.pop();
-                        self.push(a);
+                        self.push(a.clone());
                         self.push(a);
                     }
                     // Swap two values on top of the stack
@@ -307,118 +442,81 @@ impl<T: FungeInteger> Program<T> {
                     }
                     // Pop value and output as an integer followed by a space
                     '.' => {
-                        print!("{} ", self.pop());
-                        io::stdout()
-                            .flush()
+                        let a = self.pop();
+                        self.streams
+                            .print(&format!("{} ", a))
                             .context("Failed to write an integer to stdout")?;
                     }
                     // Pop value and output as ASCII character
                     ',' => {
                         let c = self.pop().to_u32().unwrap_or_default();
-                        print!("{}", char::from_u32(c).unwrap_or_default());
-                        io::stdout()
-                            .flush()
+                        self.streams
+                            .print(&char::from_u32(c).unwrap_or_default().to_string())
                             .context("Failed to write a character to stdout")?;
                     }
                     // Bridge: Skip next cell
                     '#' => self.move_cursor(),
                     // A "put" call (a way to store a value for later use).
-                    // Pop y, x, and v, then change the character at position ((x,y) + storage offset) in the program
+                    // Pop a vector and v, then change the character at position (vector + storage offset) in the program
                     // to the character with ASCII value v
                     'p' => {
-                        let (y, x, v) = (self.pop(), self.pop(), self.pop());
-                        self.put_cell(self.cursor.translate_to_storage_position((x, y)), v);
+                        let (position, v) = (self.pop_vector(), self.pop());
+                        let write_pos = self.ip().cursor.translate_to_storage_position(position);
+                        self.put_cell(write_pos, v);
                     }
                     // A "get" call (a way to retrieve data in storage).
-                    // Pop y and x, then push ASCII value of the character at the position (position + storage offset) in the program
+                    // Pop a vector, then push ASCII value of the character at the position (vector + storage offset) in the program
                     'g' => {
-                        let (y, x) = (self.pop(), self.pop());
-                        let c = self.get_cell(self.cursor.translate_to_storage_position((x, y)));
+                        let position = self.pop_vector();
+                        let c =
+                            self.get_cell(self.ip().cursor.translate_to_storage_position(position));
                         self.push(c);
                     }
                     /*
-                    Ask user for a number and push it
-                    [SPEC] Decimal input reads and discards characters until it encounters decimal digit characters,
-                    at which point it reads a decimal number from those digits, up until (but not including) the point at which
-                    input characters stop being digits, or the point where the next digit would cause a cell overflow, whichever comes first.
-
+                    Ask user for a number and push it (see `read_integer`)
                     Design choice: If input is empty or it contains characters only, the command will read 0.
                     */
                     '&' => {
-                        let mut input_text = String::new();
-                        io::stdin()
-                            .read_line(&mut input_text)
-                            .context("Failed while reading raw input from stdin")?;
-
-                        let mut res: T = T::zero();
-                        let mut discard_done = false;
-                        let mut negative = 1;
-                        for dchar in input_text.trim().chars() {
-                            if let Some(d) = dchar.to_digit(10) {
-                                if !discard_done {
-                                    discard_done = true;
-                                }
-                                let (shifted_res, mul_overflowed) =
-                                    res.overflowing_mul(&T::from(10).unwrap());
-                                // u32 -> i32 is safe here, it is just a single digit
-                                let (new_res, add_overflowed) =
-                                    shifted_res.overflowing_add(&T::from(d).unwrap());
-                                if mul_overflowed || add_overflowed {
-                                    break;
-                                }
-                                res = new_res;
-                            } else {
-                                // maybe we start reading a negative number?
-                                if discard_done {
-                                    break;
-                                } else if dchar == '-' {
-                                    negative = -1;
-                                    discard_done = true;
-                                }
-                            }
-                        }
-
-                        // check if negative underflows
-                        if negative == -1 {
-                            let (neg_res, underflowed) =
-                                res.overflowing_mul(&T::from(negative).unwrap());
-                            res = if underflowed {
-                                neg_res / T::from(10).unwrap()
-                            } else {
-                                neg_res
-                            };
-                        }
-
-                        self.push(res);
+                        let started = Instant::now();
+                        let n = read_integer(&mut self.streams)?;
+                        self.blocked_on_input(started);
+                        let n = match n {
+                            Some(n) => n,
+                            // [SPEC] Befunge-93 reads -1 at the end of input
+                            None if self.dialect == Dialect::Befunge93 => -T::one(),
+                            None => T::zero(),
+                        };
+                        self.push(n);
                     }
                     // Ask user for a character and push its ASCII value
                     '~' => {
-                        if let Some(b) = std::io::stdin().bytes().next() {
-                            let c = b.context("Failed while reading a character from stdin")?;
+                        let started = Instant::now();
+                        let b = self
+                            .streams
+                            .read_byte()
+                            .context("Failed while reading a character from stdin")?;
+                        self.blocked_on_input(started);
+                        if let Some(c) = b {
                             self.push(T::from(c).unwrap());
+                        } else if self.dialect == Dialect::Befunge93 {
+                            // [SPEC] Befunge-93 reads -1 at the end of input
+                            self.push(-T::one());
                         } else {
                             bail!("Failed to read character from stdin")
                         }
                     }
-                    // End program
-                    '@' => program_terminated = true,
+                    // End the current IP; the program ends when no IPs are left
+                    '@' => ip_terminated = true,
                     // No-op. Do nothing and skip all consecutive spaces
                     ' ' => self.skip_spaces(),
                     // Turn left
-                    '[' => self.cursor.turn_left(),
+                    '[' => self.ip_mut().cursor.turn_left(),
                     // Turn right
-                    ']' => self.cursor.turn_right(),
+                    ']' => self.ip_mut().cursor.turn_right(),
                     // Jump over i.e., execute nothing until next ";"
                     ';' => loop {
                         self.move_cursor();
-                        if char::from_u32(
-                            self.get_cell(self.cursor.position())
-                                .to_u32()
-                                .unwrap_or_default(),
-                        )
-                        .unwrap_or_default()
-                            == ';'
-                        {
+                        if self.get_char(self.ip().cursor.position()) == ';' {
                             break;
                         }
                     },
@@ -440,21 +538,21 @@ impl<T: FungeInteger> Program<T> {
                     'w' => {
                         let (b, a) = (self.pop(), self.pop());
                         match a.cmp(&b) {
-                            std::cmp::Ordering::Less => self.cursor.turn_left(),
-                            std::cmp::Ordering::Greater => self.cursor.turn_right(),
+                            std::cmp::Ordering::Less => self.ip_mut().cursor.turn_left(),
+                            std::cmp::Ordering::Greater => self.ip_mut().cursor.turn_right(),
                             std::cmp::Ordering::Equal => {}
                         }
                     }
                     // Fetch character: push ASCII of position + delta onto the stack
                     // and then jump over it
                     '\'' => {
-                        self.push(self.get_cell(self.cursor.delta() + position));
+                        self.push(self.get_cell(self.ip().cursor.delta() + position.clone()));
                         self.move_cursor(); // skip c
                     }
                     // Store character: pop ASCII value and write it into position + delta
                     's' => {
                         let c = self.pop();
-                        let write_pos = self.cursor.delta() + position;
+                        let write_pos = self.ip().cursor.delta() + position.clone();
                         self.put_cell(write_pos, c);
                         self.move_cursor(); // skip c
                     }
@@ -465,7 +563,7 @@ impl<T: FungeInteger> Program<T> {
                         let mut n = self.pop();
                         let negative = n < T::zero();
                         if negative {
-                            self.cursor.reflect();
+                            self.ip_mut().cursor.reflect();
                         }
                         n = n.abs();
                         while n > T::zero() {
@@ -473,101 +571,364 @@ impl<T: FungeInteger> Program<T> {
                             n -= T::one();
                         }
                         if negative {
-                            self.cursor.reflect();
+                            self.ip_mut().cursor.reflect();
                         }
                     }
                     // Set delta to absolute vector value
                     'x' => {
-                        let (dy, dx) = (self.pop(), self.pop());
-                        self.cursor.set_delta_members((dx, dy));
+                        let delta = self.pop_vector();
+                        self.ip_mut().cursor.set_delta_members(delta);
                     }
                     // Begin block; see specification for details
                     '{' => {
                         let n = self.pop();
-                        self.sstack.create_stack(n, self.cursor.storage_offset());
-                        // update storage offset
-                        self.cursor
-                            .set_storage_offset(self.cursor.delta() + self.cursor.position());
+                        let so = self.vector_components(self.ip().cursor.storage_offset());
+                        let ip = self.ip_mut();
+                        match ip.sstack.create_stack(n, so) {
+                            // update storage offset
+                            Some(()) => ip
+                                .cursor
+                                .set_storage_offset(ip.cursor.delta() + ip.cursor.position()),
+                            None => ip.cursor.reflect(),
+                        }
                     }
                     // End block; see specification or the `end_block` method for details
                     '}' => {
                         let n = self.pop();
-                        match self.sstack.destroy_stack(n) {
-                            Some(so) => self.cursor.set_storage_offset(so),
-                            None => self.cursor.reflect(),
+                        let dimensions = self.dialect.dimensions();
+                        match self.ip_mut().sstack.destroy_stack(n, dimensions) {
+                            Some(so) => self
+                                .ip_mut()
+                                .cursor
+                                .set_storage_offset(vector_from_components(so)),
+                            None => self.ip_mut().cursor.reflect(),
                         }
                     }
                     // Stack under stack; transfer between TOSS and SOSS
                     'u' => {
                         let count = self.pop();
-                        if self.sstack.transfer(count).is_none() {
-                            self.cursor.reflect();
+                        if self.ip_mut().sstack.transfer(count).is_none() {
+                            self.ip_mut().cursor.reflect();
+                        }
+                    }
+                    /*
+                    Input file: pop a filename, a flags cell and a vector offset, then load the file
+                    into Funge-Space at offset (relative to the storage offset) and push its size and the offset.
+                    If the least significant bit of flags is set, the file is loaded in binary mode.
+                    Reflect if the file cannot be read.
+                    */
+                    'i' => {
+                        let filename = self.pop_gnirts();
+                        let binary = self.pop() & T::one() == T::one();
+                        let offset = self.pop_vector();
+                        let storage_position = self
+                            .ip()
+                            .cursor
+                            .translate_to_storage_position(offset.clone());
+                        match self.input_file(&filename, binary, storage_position) {
+                            Ok(size) => {
+                                self.push_vector(size);
+                                self.push_vector(offset);
+                            }
+                            Err(_) => self.ip_mut().cursor.reflect(),
+                        }
+                    }
+                    /*
+                    Output file: pop a filename, a flags cell, a vector offset and a vector size, then write
+                    the rectangle of Funge-Space at offset (relative to the storage offset) with that size to the file.
+                    If the least significant bit of flags is set, the file is written in (stripped) text mode.
+                    Reflect if the file cannot be written, or if the rectangle clamped to the bounds of Funge-Space is too large.
+                    */
+                    'o' => {
+                        let filename = self.pop_gnirts();
+                        let text = self.pop() & T::one() == T::one();
+                        let offset = self.pop_vector();
+                        let size = self.pop_vector();
+                        let storage_position =
+                            self.ip().cursor.translate_to_storage_position(offset);
+                        if self
+                            .output_file(&filename, text, storage_position, size)
+                            .is_err()
+                        {
+                            self.ip_mut().cursor.reflect();
+                        }
+                    }
+                    /*
+                    Execute: pop a command and run it according to the operating paradigm, then push its exit status.
+                    Reflect if the `=` instruction is disabled or if the command could not be run.
+                    */
+                    '=' => {
+                        let command = self.pop_gnirts();
+                        match self.paradigm.execute(&command) {
+                            Ok(status) => self.push(T::from(status).unwrap_or_default()),
+                            Err(_) => self.ip_mut().cursor.reflect(),
                         }
                     }
                     // System information retrieval
                     'y' => {
                         let query = self.pop();
-                        let report = self.get_full_report();
-                        let report_length = report.len();
                         if query > T::zero() {
                             let q = query.to_usize().unwrap_or_default();
-                            if q <= report_length {
+                            match self.get_report_cell(q) {
                                 // a part of the report was requested
-                                self.push(report[report_length - q]);
-                            } else {
+                                Ok(cell) => self.push(cell),
                                 // a value outside of the report was requested
-                                let dup = self.sstack.get(q - report_length - 1);
-                                self.push(dup);
+                                Err(report_length) => {
+                                    let dup = self.ip_mut().sstack.get(q - report_length - 1);
+                                    self.push(dup);
+                                }
                             }
                         } else {
                             // the whole report was requested
-                            for cell in report {
+                            for cell in self.get_full_report() {
                                 self.push(cell);
                             }
                         }
                     }
-                    // Load semantics
+                    // Load semantics: overlay the semantics of a fingerprint, then push its ID and 1
+                    // Reflect if the fingerprint is not implemented
                     '(' => {
-                        let _fp = self.build_fingerprint();
-                        // no fingerprints implemented for now
-                        self.cursor.reflect();
+                        let fp = self.build_fingerprint();
+                        match self.fingerprints.get(&fp) {
+                            Some(fingerprint) => {
+                                self.ip_mut().load_semantics(fingerprint);
+                                self.push(fp);
+                                self.push(T::one());
+                            }
+                            None => self.ip_mut().cursor.reflect(),
+                        }
                     }
-                    // Unload semantics
+                    // Unload semantics: pop the semantics of the instructions of a fingerprint
+                    // Reflect if the fingerprint is not implemented
                     ')' => {
-                        let _fp = self.build_fingerprint();
-                        // no fingerprints implemented for now
-                        self.cursor.reflect();
+                        let fp = self.build_fingerprint();
+                        match self.fingerprints.get(&fp) {
+                            Some(fingerprint) => {
+                                self.ip_mut().unload_semantics(fingerprint.as_ref())
+                            }
+                            None => self.ip_mut().cursor.reflect(),
+                        }
                     }
-                    // Terminate program with exit code
+                    // Instructions with fingerprint semantics (or none, in which case they reflect)
+                    'A'..='Z' => self.execute_fingerprint_instruction(xchar)?,
+                    /*
+                    Split: duplicate the current IP, with the duplicate moving to the opposite direction.
+                    [SPEC] The child IP is placed in the IP list before its parent, so it is executed
+                    for the first time before its parent is executed again.
+                    */
+                    't' => {
+                        let child = self.ip().split(self.next_ip_id);
+                        self.next_ip_id += 1;
+                        self.ips.insert(self.current_ip, child);
+                        // the child moves away from the `t` cell right away,
+                        // otherwise it would split again the first time it gets executed
+                        self.ips[self.current_ip]
+                            .cursor
+                            .r#move(self.grid.get_bounds());
+                        self.current_ip += 1;
+                    }
+                    // Terminate program (i.e., all IPs) with exit code
                     'q' => {
-                        exit_code = self.pop().to_i32().unwrap_or_default();
-                        program_terminated = true;
+                        exit = Some(Exit::Quit(self.pop().to_i32().unwrap_or_default()));
                     }
                     // Every other character
                     // Note that string mode is OFF here
                     // (we checked the "ON" case before the match statement)
                     // as per the standard, we will reflect
                     // (imitating the "r" instruction which will be added later...)
-                    _ => self.cursor.reflect(),
+                    _ => self.ip_mut().cursor.reflect(),
                 }
                 k -= T::one();
             }
         } else {
-            self.cursor.reflect()
+            self.ip_mut().cursor.reflect()
         }
 
-        self.move_cursor();
-        Ok((program_terminated, exit_code))
+        if ip_terminated {
+            // the next IP in the list takes the place of the terminated one
+            self.ips.remove(self.current_ip);
+            if self.ips.is_empty() && exit.is_none() {
+                exit = Some(Exit::Ended);
+            }
+        } else {
+            self.move_cursor();
+            self.current_ip += 1;
+        }
+        // all IPs were executed, the next tick begins
+        if self.current_ip >= self.ips.len() {
+            self.current_ip = 0;
+            self.tick += 1;
+        }
+        if let Some(profile) = &mut self.profile {
+            profile.ticks = self.tick;
+            (profile.growths, profile.shrinks) = self.grid.resizes();
+        }
+
+        Ok(exit)
     }
 
-    /// Runs the Befunge program and returns an exit code
-    pub fn run(&mut self) -> Result<i32> {
+    /// Runs the program to completion and returns the way it terminated.
+    pub fn run(&mut self) -> Result<Exit> {
         loop {
-            let (program_terminated, exit_code) =
-                self.execute_current_cell().context("Runtime error")?;
-            if program_terminated {
-                return Ok(exit_code);
+            // the fast engine executes a run of simple instructions first, if there is one
+            self.execute_run(usize::MAX);
+            if let Some(exit) = self.execute_current_cell().context("Runtime error")? {
+                return Ok(exit);
             }
         }
     }
+
+    /**
+    Runs the program for (at most) `ticks` ticks i.e., until every IP has executed
+    `ticks` instructions, and returns the way it terminated, if it did in the meantime.
+
+    A program that did not terminate can be run further, with `run` or `run_for`.
+    */
+    pub fn run_for(&mut self, mut ticks: usize) -> Result<Option<Exit>> {
+        while ticks > 0 {
+            // the fast engine executes a run of simple instructions first, if there is one
+            ticks -= self.execute_run(ticks);
+            if ticks == 0 {
+                break;
+            }
+            ticks -= 1;
+            loop {
+                if let Some(exit) = self.execute_current_cell().context("Runtime error")? {
+                    return Ok(Some(exit));
+                }
+                // all IPs were executed, the tick is over
+                if self.current_ip == 0 {
+                    break;
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /**
+    Executes a single instruction i.e., the instruction of the IP that executes next,
+    and returns what happened; see `Step`.
+
+    Fails if the program has already terminated.
+    */
+    pub fn step(&mut self) -> Result<Step<T>> {
+        if self.ips.is_empty() {
+            bail!("The program has already terminated");
+        }
+        let ip = self.ip();
+        let ip_id = ip.id;
+        let string_mode = ip.string_mode;
+        let position_before = ip.cursor.position();
+        let delta_before = ip.cursor.delta_members();
+        let toss_before = IpState(ip).toss().to_vec();
+        let instruction = self.get_cell(position_before.clone());
+
+        self.written_cells = Some(Vec::new());
+        let result = self.execute_current_cell().context("Runtime error");
+        let written = self.written_cells.take().unwrap_or_default();
+        let exit = result?;
+
+        let ip_after = self.ips.iter().find(|ip| ip.id == ip_id);
+        let toss_after = match ip_after {
+            Some(ip) => IpState(ip).toss().to_vec(),
+            None => toss_before.clone(),
+        };
+        // the values below the first difference of the two TOSSes were left untouched
+        let untouched = toss_before
+            .iter()
+            .zip(toss_after.iter())
+            .take_while(|(a, b)| a == b)
+            .count();
+
+        Ok(Step {
+            ip_id,
+            instruction,
+            string_mode,
+            position_before,
+            delta_before,
+            position_after: ip_after.map(|ip| ip.cursor.position()),
+            delta_after: ip_after.map(|ip| ip.cursor.delta_members()),
+            popped: toss_before[untouched..].to_vec(),
+            pushed: toss_after[untouched..].to_vec(),
+            written,
+            exit,
+        })
+    }
+}
+
+/// Builds a vector out of (at most three of) its `components`; the missing ones are 0.
+fn vector_from_components<T: FungeInteger>(components: Vec<T>) -> (T, T, T) {
+    let mut components = components.into_iter();
+    (
+        components.next().unwrap_or_default(),
+        components.next().unwrap_or_default(),
+        components.next().unwrap_or_default(),
+    )
+}
+
+/**
+Reads a line from stdin and returns the decimal number in it, as the `&` instruction requires,
+or `None` at the end of input.
+
+[SPEC] Decimal input reads and discards characters until it encounters decimal digit characters,
+at which point it reads a decimal number from those digits, up until (but not including) the point at which
+input characters stop being digits, or the point where the next digit would cause a cell overflow, whichever comes first.
+*/
+fn read_integer<T: FungeInteger>(streams: &mut Streams) -> Result<Option<T>> {
+    let input_text = match streams
+        .read_line()
+        .context("Failed while reading raw input from stdin")?
+    {
+        Some(line) => line,
+        None => return Ok(None),
+    };
+
+    let mut res: T = T::zero();
+    let mut discard_done = false;
+    let mut negative = 1;
+    for dchar in input_text.trim().chars() {
+        if let Some(d) = dchar.to_digit(10) {
+            if !discard_done {
+                discard_done = true;
+            }
+            let (shifted_res, mul_overflowed) = res.overflowing_mul(&T::from(10).unwrap());
+            // u32 -> i32 is safe here, it is just a single digit
+            let (new_res, add_overflowed) = shifted_res.overflowing_add(&T::from(d).unwrap());
+            if mul_overflowed || add_overflowed {
+                break;
+            }
+            res = new_res;
+        } else {
+            // maybe we start reading a negative number?
+            if discard_done {
+                break;
+            } else if dchar == '-' {
+                negative = -1;
+                discard_done = true;
+            }
+        }
+    }
+
+    // check if negative underflows
+    if negative == -1 {
+        let (neg_res, underflowed) = res.overflowing_mul(&T::from(negative).unwrap());
+        res = if underflowed {
+            neg_res / T::from(10).unwrap()
+        } else {
+            neg_res
+        };
+    }
+
+    Ok(Some(res))
+}
+
+/**
+Asks the user for the result of `operation` (a division or remainder by 0),
+like Befunge-93 does, and returns it; an answer without a number is 0.
+*/
+fn ask_for_result<T: FungeInteger>(streams: &mut Streams, operation: &str) -> Result<T> {
+    streams
+        .print(&format!("What do you want {} to be? ", operation))
+        .context("Failed to write a question to stdout")?;
+    Ok(read_integer(streams)?.unwrap_or_default())
 }
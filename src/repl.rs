@@ -1,9 +1,11 @@
 use anyhow::{Context, Result};
 use std::{io, io::prelude::*};
 
-use crate::program::Program;
+use bff::{Dialect, FungeInteger, Program};
 
-pub fn start() -> Result<()> {
+use crate::stderr::Stderr;
+
+pub fn start<T: FungeInteger>() -> Result<()> {
     println!("{} - Unefunge 98 REPL", env!("CARGO_PKG_NAME"));
     println!("version {}", env!("CARGO_PKG_VERSION"));
     println!("(type \"exit\" or \"quit\" and press <Enter> or press <Ctrl> + C to quit)");
@@ -35,8 +37,11 @@ pub fn start() -> Result<()> {
         code.push('@');
 
         // step 6: evaluate code
-        let mut program = Program::<i32>::from(vec![code.into_bytes()]);
-        program.run()?;
+        Program::<T>::builder(code)
+            .dialect(Dialect::Unefunge)
+            .stderr(Stderr::default())
+            .build()
+            .run()?;
 
         println!();
     }
@@ -0,0 +1,55 @@
+use ratatui::crossterm::style::Stylize;
+use std::io::{self, IsTerminal, Write};
+
+/// The prefix of the warnings that programs write to their stderr.
+const WARNING: &[u8] = b"warning: ";
+
+/**
+The stderr that the programs run by `bff` write to: it passes everything on to the stderr
+of the process a line at a time, so that it can colour the prefix of each warning yellow,
+when stderr is a terminal.
+*/
+#[derive(Default)]
+pub struct Stderr {
+    /// The part of the current line written so far
+    line: Vec<u8>,
+}
+
+impl Stderr {
+    /// Writes `lines` to the stderr of the process, colouring the prefix of every warning.
+    fn write_lines(lines: &[u8]) -> io::Result<()> {
+        let mut stderr = io::stderr().lock();
+        let colour = stderr.is_terminal();
+        for line in lines.split_inclusive(|&b| b == b'\n') {
+            match line.strip_prefix(WARNING) {
+                Some(message) if colour => {
+                    write!(stderr, "{}", "warning: ".yellow())?;
+                    stderr.write_all(message)?;
+                }
+                _ => stderr.write_all(line)?,
+            }
+        }
+        stderr.flush()
+    }
+}
+
+impl Write for Stderr {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.line.extend_from_slice(buf);
+        if let Some(end) = self.line.iter().rposition(|&b| b == b'\n') {
+            let rest = self.line.split_off(end + 1);
+            Self::write_lines(&std::mem::replace(&mut self.line, rest))?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Self::write_lines(&std::mem::take(&mut self.line))
+    }
+}
+
+impl Drop for Stderr {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
@@ -0,0 +1,519 @@
+use anyhow::{bail, Context, Result};
+use bff::{format_value, Exit, FungeInteger, Program, ProgramBuilder};
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+use std::{
+    cell::RefCell,
+    collections::BTreeSet,
+    io::{self, IsTerminal},
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+/// The speeds (in steps per second) the program can be played at.
+const SPEEDS: [f64; 12] = [
+    1.0, 2.0, 5.0, 10.0, 20.0, 50.0, 100.0, 200.0, 500.0, 1000.0, 10000.0, 100000.0,
+];
+/// The speed the program is played at initially, as an index in `SPEEDS`.
+const INITIAL_SPEED: usize = 3;
+/// The longest time between two frames.
+const FRAME: Duration = Duration::from_millis(33);
+
+const KEYS: &str = "space play/pause  s step  +/- speed  arrows/pgup/pgdn scroll  \
+                    f follow  c centre  < > plane  q quit";
+
+/// An output stream that the visualiser shows, in its output pane.
+#[derive(Clone, Default)]
+struct Pane(Rc<RefCell<Vec<u8>>>);
+
+impl io::Write for Pane {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// The state of the program (as far as the visualiser is concerned).
+enum State {
+    Paused,
+    Playing,
+    Terminated(Exit),
+    Failed(String),
+}
+
+/**
+A full-screen visualiser, which shows a `Program` as it runs one step at a time:
+the visible region of Funge-Space, the IPs in it, the stack stack and the output.
+*/
+struct Visualiser<T: FungeInteger> {
+    program: Program<T>,
+    output: Pane,
+    state: State,
+    /// The index of the speed in `SPEEDS`
+    speed: usize,
+    /// The steps that are due but not yet executed, while playing
+    due: f64,
+    steps: u64,
+    /// The position of the top left cell of the visible region
+    origin: (T, T, T),
+    /// The size of the visible region, as of the last frame
+    size: (usize, usize),
+    /// Whether the visible region moves so that the IP that executes next stays in it
+    follow: bool,
+    /// The cells that were written to during the last step
+    written_last: Vec<(T, T, T)>,
+    /// The cells that were written to since the program started
+    written: BTreeSet<(T, T, T)>,
+}
+
+pub fn start<T: FungeInteger>(builder: ProgramBuilder<T>) -> Result<()> {
+    if !io::stdout().is_terminal() {
+        bail!("The visualiser needs stdout to be a terminal");
+    }
+    let output = Pane::default();
+    let builder = builder.stdout(output.clone()).stderr(output.clone());
+    // the terminal belongs to the visualiser, so the program can only read redirected input
+    let builder = if io::stdin().is_terminal() {
+        builder.stdin(io::empty())
+    } else {
+        builder
+    };
+    let mut visualiser = Visualiser::new(builder.build(), output);
+
+    let mut terminal = ratatui::try_init().context("Failed to set up the terminal")?;
+    let result = visualiser.run(&mut terminal);
+    ratatui::restore();
+    result
+}
+
+impl<T: FungeInteger> Visualiser<T> {
+    fn new(program: Program<T>, output: Pane) -> Self {
+        let origin = program.bounds().0;
+        Self {
+            program,
+            output,
+            state: State::Paused,
+            speed: INITIAL_SPEED,
+            due: 0.0,
+            steps: 0,
+            origin,
+            size: (0, 0),
+            follow: true,
+            written_last: Vec::new(),
+            written: BTreeSet::new(),
+        }
+    }
+
+    fn run(&mut self, terminal: &mut ratatui::DefaultTerminal) -> Result<()> {
+        let mut last = Instant::now();
+        loop {
+            terminal
+                .draw(|frame| self.draw(frame))
+                .context("Failed to draw the visualiser")?;
+
+            let timeout = match self.state {
+                State::Playing => FRAME.min(Duration::from_secs_f64(1.0 / SPEEDS[self.speed])),
+                _ => FRAME,
+            };
+            if event::poll(timeout).context("Failed to read terminal events")? {
+                if let Event::Key(key) = event::read().context("Failed to read terminal events")? {
+                    if key.kind == KeyEventKind::Press && !self.handle_key(key) {
+                        return Ok(());
+                    }
+                }
+            }
+
+            let now = Instant::now();
+            if let State::Playing = self.state {
+                self.due += (now - last).as_secs_f64() * SPEEDS[self.speed];
+                // the frame must be drawn in time, however fast the program is played
+                while self.due >= 1.0 && now.elapsed() < FRAME {
+                    self.due -= 1.0;
+                    self.step();
+                    if !matches!(self.state, State::Playing) {
+                        break;
+                    }
+                }
+                self.due = self.due.min(SPEEDS[self.speed]);
+            }
+            last = now;
+        }
+    }
+
+    /// Handles a key press and returns whether the visualiser goes on.
+    fn handle_key(&mut self, key: KeyEvent) -> bool {
+        let (width, height) = (self.size.0.max(1), self.size.1.max(1));
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return false,
+            KeyCode::Char(' ') => {
+                self.state = match self.state {
+                    State::Paused => State::Playing,
+                    State::Playing => State::Paused,
+                    _ => return true,
+                };
+                self.due = 0.0;
+            }
+            KeyCode::Char('s') | KeyCode::Char('n') | KeyCode::Enter => {
+                if let State::Paused | State::Playing = self.state {
+                    self.state = State::Paused;
+                    self.step();
+                }
+            }
+            KeyCode::Char('+') | KeyCode::Char('=') => {
+                self.speed = (self.speed + 1).min(SPEEDS.len() - 1);
+            }
+            KeyCode::Char('-') => self.speed = self.speed.saturating_sub(1),
+            KeyCode::Char('f') => self.follow = !self.follow,
+            KeyCode::Char('c') => self.centre(),
+            KeyCode::Left => self.scroll(-1, 0, 0),
+            KeyCode::Right => self.scroll(1, 0, 0),
+            KeyCode::Up => self.scroll(0, -1, 0),
+            KeyCode::Down => self.scroll(0, 1, 0),
+            KeyCode::PageUp => self.scroll(0, -(height as i64), 0),
+            KeyCode::PageDown => self.scroll(0, height as i64, 0),
+            KeyCode::Home => self.scroll(-(width as i64), 0, 0),
+            KeyCode::End => self.scroll(width as i64, 0, 0),
+            KeyCode::Char('<') if self.program.dialect().dimensions() == 3 => self.scroll(0, 0, -1),
+            KeyCode::Char('>') if self.program.dialect().dimensions() == 3 => self.scroll(0, 0, 1),
+            _ => {}
+        }
+        true
+    }
+
+    fn step(&mut self) {
+        match self.program.step() {
+            Ok(step) => {
+                self.steps += 1;
+                self.written.extend(step.written.iter().cloned());
+                self.written_last = step.written;
+                if let Some(exit) = step.exit {
+                    self.state = State::Terminated(exit);
+                }
+            }
+            Err(e) => self.state = State::Failed(e.to_string()),
+        }
+    }
+
+    /**
+    Moves the visible region by the given number of cells, wrapping around the limits of a cell
+    (like `draw_grid` does); scrolling stops following the IP.
+    */
+    fn scroll(&mut self, x: i64, y: i64, z: i64) {
+        let (ox, oy, oz) = &mut self.origin;
+        for (component, by) in [(ox, x), (oy, y), (oz, z)] {
+            *component = component
+                .overflowing_add(&T::from(by).unwrap_or_else(T::zero))
+                .0;
+        }
+        self.follow = false;
+    }
+
+    /// Moves the visible region so that the IP that executes next is at its centre.
+    fn centre(&mut self) {
+        if let Some(ip) = self.program.current_ip() {
+            let (x, y, z) = ip.position();
+            let half = |n: usize| T::from(n / 2).unwrap_or_else(T::zero);
+            self.origin = (
+                x.overflowing_sub(&half(self.size.0)).0,
+                y.overflowing_sub(&half(self.size.1)).0,
+                z,
+            );
+        }
+    }
+
+    /// Moves the visible region, if the IP that executes next is (about to get) out of it.
+    fn keep_ip_visible(&mut self) {
+        let position = match self.program.current_ip() {
+            Some(ip) => ip.position(),
+            None => return,
+        };
+        let visible =
+            |p: &T, o: &T, n: usize| p.overflowing_sub(o).0.to_usize().is_some_and(|d| d < n);
+        let (x, y, z) = &position;
+        let (ox, oy, oz) = &self.origin;
+        if !visible(x, ox, self.size.0) || !visible(y, oy, self.size.1) || z != oz {
+            self.centre();
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [main, output, status] = Layout::vertical([
+            Constraint::Min(5),
+            Constraint::Length(8),
+            Constraint::Length(2),
+        ])
+        .areas(frame.area());
+        let [grid, side] =
+            Layout::horizontal([Constraint::Min(10), Constraint::Length(32)]).areas(main);
+
+        self.draw_grid(frame, grid);
+        self.draw_side(frame, side);
+        self.draw_output(frame, output);
+        self.draw_status(frame, status);
+    }
+
+    fn draw_grid(&mut self, frame: &mut Frame, area: Rect) {
+        // the borders take a cell on each side
+        self.size = (
+            area.width.saturating_sub(2) as usize,
+            area.height.saturating_sub(2) as usize,
+        );
+        if self.follow {
+            self.keep_ip_visible();
+        }
+
+        let next_ip = self.program.current_ip().map(|ip| ip.position());
+        let other_ips: Vec<(T, T, T)> = self.program.ips().map(|ip| ip.position()).collect();
+        let (ox, oy, oz) = self.origin.clone();
+
+        let mut lines = Vec::with_capacity(self.size.1);
+        let mut y = oy.clone();
+        for _ in 0..self.size.1 {
+            let mut spans = Vec::with_capacity(self.size.0);
+            let mut x = ox.clone();
+            for _ in 0..self.size.0 {
+                let position = (x.clone(), y.clone(), oz.clone());
+                let value = self.program.cell(position.clone());
+                let style = if next_ip.as_ref() == Some(&position) {
+                    Style::new().fg(Color::Black).bg(Color::Green)
+                } else if other_ips.contains(&position) {
+                    Style::new().fg(Color::Black).bg(Color::Cyan)
+                } else if self.written_last.contains(&position) {
+                    Style::new().fg(Color::Black).bg(Color::Yellow)
+                } else if self.written.contains(&position) {
+                    Style::new().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+                } else {
+                    Style::new()
+                };
+                spans.push(Span::styled(cell_char(&value).to_string(), style));
+                // the visible region wraps around the limits of a cell
+                x = x.overflowing_add(&T::one()).0;
+            }
+            lines.push(Line::from(spans));
+            y = y.overflowing_add(&T::one()).0;
+        }
+
+        let mut title = format!(" Funge-Space from {} ", self.format_vector(&self.origin));
+        if self.follow {
+            title.push_str("(following) ");
+        }
+        frame.render_widget(
+            Paragraph::new(lines).block(Block::new().borders(Borders::ALL).title(title)),
+            area,
+        );
+    }
+
+    fn draw_side(&self, frame: &mut Frame, area: Rect) {
+        let mut lines = Vec::new();
+        let (min, max) = self.program.bounds();
+        lines.push(Line::from(format!("Bounds {}", self.format_vector(&min))));
+        lines.push(Line::from(format!("    to {}", self.format_vector(&max))));
+        lines.push(Line::from(format!("IPs    {}", self.program.ips().count())));
+        lines.push(Line::from(""));
+
+        if let Some(ip) = self.program.current_ip() {
+            let delta = ip.delta();
+            lines.push(Line::styled(
+                format!("IP {}", ip.id()),
+                Style::new().add_modifier(Modifier::BOLD),
+            ));
+            lines.push(Line::from(format!(
+                "position {}",
+                self.format_vector(&ip.position())
+            )));
+            lines.push(Line::from(format!(
+                "delta    {} {}",
+                self.format_vector(&delta),
+                arrow(&delta)
+            )));
+            lines.push(Line::from(format!(
+                "offset   {}",
+                self.format_vector(&ip.storage_offset())
+            )));
+            if ip.string_mode() {
+                lines.push(Line::from("string mode"));
+            }
+            lines.push(Line::from(""));
+
+            // the TOSS first, topmost values first
+            for (i, stack) in ip.stacks().iter().rev().enumerate() {
+                let name = match i {
+                    0 => "TOSS".to_string(),
+                    1 => "SOSS".to_string(),
+                    _ => format!("stack {}", i),
+                };
+                lines.push(Line::styled(
+                    format!("{} ({})", name, stack.len()),
+                    Style::new().add_modifier(Modifier::BOLD),
+                ));
+                for value in stack.iter().rev() {
+                    lines.push(Line::from(format!("  {}", format_value(value))));
+                }
+            }
+        }
+
+        frame.render_widget(
+            Paragraph::new(lines).block(Block::new().borders(Borders::ALL).title(" IP ")),
+            area,
+        );
+    }
+
+    fn draw_output(&self, frame: &mut Frame, area: Rect) {
+        let output = String::from_utf8_lossy(&self.output.0.borrow()).into_owned();
+        let height = area.height.saturating_sub(2) as usize;
+        let lines: Vec<&str> = output.split('\n').collect();
+        let lines: Vec<Line> = lines[lines.len().saturating_sub(height)..]
+            .iter()
+            .map(|line| Line::from(line.to_string()))
+            .collect();
+        frame.render_widget(
+            Paragraph::new(lines).block(Block::new().borders(Borders::ALL).title(" Output ")),
+            area,
+        );
+    }
+
+    fn draw_status(&self, frame: &mut Frame, area: Rect) {
+        let state = match &self.state {
+            State::Paused => Span::styled("paused", Style::new().fg(Color::Yellow)),
+            State::Playing => Span::styled("playing", Style::new().fg(Color::Green)),
+            State::Terminated(Exit::Ended) => Span::raw("the program ended"),
+            State::Terminated(Exit::Quit(code)) => {
+                Span::raw(format!("the program quit with exit code {}", code))
+            }
+            State::Failed(e) => Span::styled(format!("error: {}", e), Style::new().fg(Color::Red)),
+        };
+        let lines = vec![
+            Line::from(vec![
+                state,
+                Span::raw(format!(
+                    " | {} steps/s | step {}",
+                    SPEEDS[self.speed], self.steps
+                )),
+            ]),
+            Line::styled(KEYS, Style::new().add_modifier(Modifier::DIM)),
+        ];
+        frame.render_widget(Paragraph::new(lines), area);
+    }
+
+    /// Formats a vector, with one component per dimension of the program.
+    fn format_vector(&self, vector: &(T, T, T)) -> String {
+        bff::format_vector(vector, self.program.dialect().dimensions())
+    }
+}
+
+/// Returns the character a cell is shown as; the unprintable values are shown as `·`.
+fn cell_char<T: FungeInteger>(value: &T) -> char {
+    match value.to_u32().and_then(char::from_u32) {
+        Some(c) if !c.is_control() => c,
+        _ => '·',
+    }
+}
+
+/// Returns an arrow that points where `delta` does, on the plane of the screen.
+fn arrow<T: FungeInteger>((x, y, z): &(T, T, T)) -> char {
+    let sign = |c: &T| c.signum().to_i8().unwrap_or_default();
+    match (sign(x), sign(y), sign(z)) {
+        (0, 0, 0) => '•',
+        (0, 0, 1) => '⊗',
+        (0, 0, _) => '⊙',
+        (1, 0, _) => '→',
+        (-1, 0, _) => '←',
+        (0, -1, _) => '↑',
+        (0, _, _) => '↓',
+        (1, -1, _) => '↗',
+        (1, _, _) => '↘',
+        (_, -1, _) => '↖',
+        _ => '↙',
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::{backend::TestBackend, Terminal};
+
+    fn render(visualiser: &mut Visualiser<i32>) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(80, 30)).unwrap();
+        terminal.draw(|frame| visualiser.draw(frame)).unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..buffer.area.height)
+            .map(|y| {
+                (0..buffer.area.width)
+                    .map(|x| buffer[(x, y)].symbol())
+                    .collect::<String>()
+                    + "\n"
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_draw() {
+        let output = Pane::default();
+        let program = Program::builder("12+\"A\"f0p.@\n")
+            .stdout(output.clone())
+            .build();
+        let mut visualiser = Visualiser::new(program, output);
+        for _ in 0..9 {
+            visualiser.step();
+        }
+
+        let screen = render(&mut visualiser);
+        assert!(screen.contains("│12+\"A\"f0p.@    A"));
+        assert!(screen.contains("delta    (1, 0) →"));
+        assert!(screen.contains("TOSS (1)"));
+        assert!(screen.contains("  3"));
+        assert!(screen.contains("step 9"));
+        assert_eq!(visualiser.written_last, vec![(15, 0, 0)]);
+
+        visualiser.step();
+        visualiser.step();
+        let screen = render(&mut visualiser);
+        assert!(screen.contains("│3 "));
+        assert!(screen.contains("the program"));
+    }
+
+    #[test]
+    fn test_scrolling() {
+        let program = Program::builder("v\n>").build();
+        let mut visualiser = Visualiser::new(program, Pane::default());
+        render(&mut visualiser);
+        visualiser.scroll(-3, -2, 0);
+        assert!(!visualiser.follow);
+
+        let screen = render(&mut visualiser);
+        assert!(screen.contains("Funge-Space from (-3, -2)"));
+        assert!(screen.contains("│   v"));
+        assert_eq!(arrow(&(0, -2, 0)), '↑');
+        assert_eq!(arrow(&(-1, 1, 0)), '↙');
+    }
+
+    #[test]
+    fn test_scrolling_at_the_limits_of_a_cell() {
+        let program = Program::builder("v\n>").build();
+        let mut visualiser = Visualiser::new(program, Pane::default());
+        render(&mut visualiser);
+        visualiser.origin = (i32::MAX - 2, i32::MAX - 2, 0);
+        visualiser.follow = false;
+        render(&mut visualiser);
+        visualiser.scroll(5, 5, 0);
+        assert_eq!(visualiser.origin, (i32::MIN + 2, i32::MIN + 2, 0));
+
+        let screen = render(&mut visualiser);
+        assert!(screen.contains(&format!(
+            "Funge-Space from ({}, {})",
+            i32::MIN + 2,
+            i32::MIN + 2
+        )));
+    }
+}
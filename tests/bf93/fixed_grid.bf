@@ -0,0 +1 @@
+"A"99*0p99*0g,@
\ No newline at end of file
@@ -0,0 +1 @@
+"a  b">:#,_@
\ No newline at end of file
@@ -0,0 +1,4 @@
+7y.1m
+
+    >111g,"KO",,@
+ Z
@@ -0,0 +1,104 @@
+use anyhow::{ensure, Result};
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::path::PathBuf;
+
+/**
+Compiles the source file `filename` with `bff compile` and builds the Rust program with `rustc`,
+then returns the path of the executable.
+*/
+fn compile(filename: &str) -> Result<PathBuf> {
+    let name = filename.replace(['/', '.'], "_");
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR"));
+    let (source, executable) = (dir.join(format!("{}.rs", name)), dir.join(name));
+
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    cmd.arg("compile")
+        .arg(filename)
+        .arg("-o")
+        .arg(&source)
+        .assert()
+        .success();
+    let status = std::process::Command::new("rustc")
+        .args(["--edition", "2021", "-D", "warnings", "-o"])
+        .arg(&executable)
+        .arg(&source)
+        .status()?;
+    ensure!(status.success(), "rustc failed to build {}", filename);
+    Ok(executable)
+}
+
+/// Runs the source file `filename`, compiled and in bff, with `stdin`, and compares the two.
+fn compare(filename: &str, stdin: &str) -> Result<()> {
+    let mut interpreter = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    let expected = interpreter.arg(filename).write_stdin(stdin).output()?;
+    Command::new(compile(filename)?)
+        .write_stdin(stdin)
+        .assert()
+        .code(expected.status.code().unwrap_or_default())
+        .stdout(expected.stdout)
+        .stderr(expected.stderr);
+
+    Ok(())
+}
+
+#[test]
+fn test_befunge_93() -> Result<()> {
+    for (name, stdin) in [
+        ("sanity", ""),
+        ("hello_world", ""),
+        ("factorial", "10"),
+        ("primecheck", "7919"),
+        ("compare", "53\n-123"),
+        ("div_by_zero", "3"),
+        ("quine", ""),
+        ("echo", "Hello\n"),
+        ("echo_int", "-42\n"),
+        ("fixed_grid", ""),
+    ] {
+        compare(&format!("tests/bf93/{}.bf", name), stdin)?;
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_befunge_98() -> Result<()> {
+    for name in ["hello_world", "pg_out", "far_apart", "least_division"] {
+        compare(&format!("tests/bf98/{}.b98", name), "")?;
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_self_modifying() -> Result<()> {
+    // `p` puts a `q` on the path of the IP, so the interpreter takes over and quits
+    Command::new(compile("tests/bf98/self_modifying.b98")?)
+        .assert()
+        .code(42)
+        .stdout("7 5 ");
+
+    Ok(())
+}
+
+#[test]
+fn test_unsupported_instruction() -> Result<()> {
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    cmd.args(["compile", "tests/bf98/k1.b98"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "The `k` instruction on (4, 0) can not be compiled",
+        ));
+
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    cmd.args(["compile", "--trefunge", "tests/bf98/trefunge.tf"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Only Befunge-93 and Befunge-98 programs can be compiled",
+        ));
+
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use anyhow::Result;
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+const FILE: &str = "tests/bf98/debug.b98";
+
+#[test]
+fn test_breakpoints() -> Result<()> {
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    cmd.args(["debug", FILE])
+        .write_stdin("break cell 8 0\nc\nstack\nwatch 15 0\nc\nset stack 0 7\nc\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Breakpoint 1\nIP 0 at (8, 0): 112 'p'",
+        ))
+        .stdout(predicate::str::contains("TOSS: [3 65 15 0]"))
+        .stdout(predicate::str::contains(
+            "Watchpoint 2: (15, 0) is now 65 'A'",
+        ))
+        .stdout(predicate::str::contains("7 \nThe program ended"));
+
+    Ok(())
+}
+
+#[test]
+fn test_stepping() -> Result<()> {
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    cmd.args(["debug", FILE])
+        .write_stdin("step 3\nstack\nbreak instruction .\ndelete 1\nc\nstep\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("IP 0 at (3, 0): 34 '\"'"))
+        .stdout(predicate::str::contains("TOSS: [3]"))
+        .stdout(predicate::str::contains("3 \nThe program ended"))
+        .stdout(predicate::str::contains("error: "));
+
+    Ok(())
+}
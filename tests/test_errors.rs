@@ -7,7 +7,7 @@ const TESTDIR: &str = "tests/bf93/";
 const SUFFIX: &str = ".bf";
 
 fn testcase(name: &str) -> String {
-    TESTDIR.to_owned() + name + &SUFFIX.to_owned()
+    TESTDIR.to_owned() + name + SUFFIX
 }
 
 #[test]
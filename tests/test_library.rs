@@ -0,0 +1,252 @@
+use anyhow::Result;
+use bff::{BigInt, Clock, DateTime, Dialect, Engine, Exit, Program, Step, Trace, Utc};
+use std::{cell::RefCell, io, rc::Rc};
+
+/// An output stream whose contents can still be read once it is handed to a program.
+#[derive(Clone, Default)]
+struct Output(Rc<RefCell<Vec<u8>>>);
+
+impl Output {
+    fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.borrow()).into_owned()
+    }
+}
+
+impl io::Write for Output {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_streams() -> Result<()> {
+    let (stdout, stderr) = (Output::default(), Output::default());
+    let mut program = Program::<i32>::builder("~,&.fffff****.@")
+        .dialect(Dialect::Unefunge)
+        .stdin(&b"x42\n"[..])
+        .stdout(stdout.clone())
+        .stderr(stderr.clone())
+        .build();
+
+    assert_eq!(program.run()?, Exit::Ended);
+    assert_eq!(stdout.contents(), "x42 759375 ");
+    assert_eq!(stderr.contents(), "");
+
+    Ok(())
+}
+
+#[test]
+fn test_warnings() -> Result<()> {
+    let (stdout, stderr) = (Output::default(), Output::default());
+    let mut program = Program::<i32>::builder("10/.@")
+        .stdout(stdout.clone())
+        .stderr(stderr.clone())
+        .build();
+
+    assert_eq!(program.run()?, Exit::Ended);
+    assert_eq!(stdout.contents(), "0 ");
+    assert!(stderr.contents().starts_with("warning: Division by 0"));
+
+    Ok(())
+}
+
+/// A clock that is always at the same time.
+struct FixedClock(DateTime<Utc>);
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.0
+    }
+}
+
+#[test]
+fn test_clock() -> Result<()> {
+    // `y` pushes the date and the time, then `HRTI` the microseconds and the elapsed time
+    let stdout = Output::default();
+    let mut program = Program::<i32>::builder("45*y.37*y.\"ITRH\"4(S.MT.@")
+        .clock(FixedClock("2022-02-04T12:34:56.000250Z".parse()?))
+        .stdout(stdout.clone())
+        .build();
+
+    assert_eq!(program.run()?, Exit::Ended);
+    assert_eq!(
+        stdout.contents(),
+        format!(
+            "{} {} 250 0 ",
+            (122 << 16) + (2 << 8) + 4,
+            (12 << 16) + (34 << 8) + 56
+        )
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_run_for() -> Result<()> {
+    let stdout = Output::default();
+    // every tick prints a single number
+    let mut program = Program::<BigInt>::builder("1.2.3.4.@")
+        .stdout(stdout.clone())
+        .build();
+
+    assert_eq!(program.run_for(4)?, None);
+    assert_eq!(stdout.contents(), "1 2 ");
+    assert_eq!(program.run_for(100)?, Some(Exit::Ended));
+    assert_eq!(stdout.contents(), "1 2 3 4 ");
+
+    Ok(())
+}
+
+#[test]
+fn test_fast_engine() -> Result<()> {
+    let stdout = Output::default();
+    // a run of 9 simple instructions is still executed one per tick
+    let mut program = Program::<i32>::builder("12+3*:*5-.@")
+        .engine(Engine::Fast)
+        .stdout(stdout.clone())
+        .build();
+
+    assert_eq!(program.run_for(5)?, None);
+    assert_eq!(program.current_ip().unwrap().toss(), &[9]);
+    assert_eq!(program.run_for(3)?, None);
+    assert_eq!(program.current_ip().unwrap().toss(), &[81, 5]);
+    assert_eq!(program.current_ip().unwrap().position(), (8, 0, 0));
+    assert_eq!(program.run_for(2)?, None);
+    assert_eq!(stdout.contents(), "76 ");
+    assert_eq!(program.run_for(100)?, Some(Exit::Ended));
+
+    Ok(())
+}
+
+#[test]
+fn test_exit_code() -> Result<()> {
+    let mut program = Program::<i64>::builder("\"*\"q").build();
+    let exit = program.run()?;
+    assert_eq!(exit, Exit::Quit(42));
+    assert_eq!(exit.code(), 42);
+
+    Ok(())
+}
+
+#[test]
+fn test_step() -> Result<()> {
+    let mut program = Program::<i32>::builder("12+v\n   @")
+        .stdout(io::sink())
+        .build();
+
+    program.step()?;
+    let step = program.step()?;
+    assert_eq!(step.instruction_char(), Some('2'));
+    assert_eq!(step.popped, vec![]);
+    assert_eq!(step.pushed, vec![2]);
+
+    let step = program.step()?;
+    assert_eq!(
+        step,
+        Step {
+            ip_id: 0,
+            instruction: '+' as i32,
+            string_mode: false,
+            position_before: (2, 0, 0),
+            delta_before: (1, 0, 0),
+            position_after: Some((3, 0, 0)),
+            delta_after: Some((1, 0, 0)),
+            popped: vec![1, 2],
+            pushed: vec![3],
+            written: vec![],
+            exit: None,
+        }
+    );
+
+    let step = program.step()?;
+    assert_eq!(step.delta_after, Some((0, 1, 0)));
+    assert_eq!(step.position_after, Some((3, 1, 0)));
+
+    let step = program.step()?;
+    assert_eq!(step.instruction_char(), Some('@'));
+    assert_eq!(step.position_after, None);
+    assert_eq!(step.exit, Some(Exit::Ended));
+    assert!(program.step().is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_state_inspection() -> Result<()> {
+    let mut program = Program::<i32>::builder("5\"a\"1{\"b").build();
+    assert_eq!(program.bounds(), ((0, 0, 0), (7, 0, 0)));
+    assert_eq!(program.cell((4, 0, 0)), '1' as i32);
+    assert_eq!(program.cell((100, 100, 0)), ' ' as i32);
+
+    for _ in 0..7 {
+        program.step()?;
+    }
+    let ip = program.current_ip().unwrap();
+    assert_eq!(ip.id(), 0);
+    assert_eq!(ip.position(), (7, 0, 0));
+    assert_eq!(ip.storage_offset(), (6, 0, 0));
+    assert!(ip.string_mode());
+    assert_eq!(ip.stacks().len(), 2);
+    assert_eq!(ip.toss(), &['a' as i32]);
+    assert_eq!(ip.soss(), Some(&[5, 0, 0][..]));
+    assert_eq!(program.ips().count(), 1);
+
+    Ok(())
+}
+
+#[test]
+fn test_trace() -> Result<()> {
+    let trace = Output::default();
+    let mut program = Program::<i64>::builder("1\"a\"-.@")
+        .stdout(io::sink())
+        .trace(Trace::new(trace.clone()).top(1).instructions("\"@"))
+        .build();
+    program.run()?;
+
+    assert_eq!(
+        trace.contents(),
+        "{\"tick\":1,\"ip\":0,\"position\":[1,0],\"delta\":[1,0],\"offset\":[0,0],\
+         \"instruction\":\"\\\"\",\"value\":34,\"string_mode\":false,\"toss\":[1],\
+         \"toss_size\":1,\"stacks\":1}\n\
+         {\"tick\":3,\"ip\":0,\"position\":[3,0],\"delta\":[1,0],\"offset\":[0,0],\
+         \"instruction\":\"\\\"\",\"value\":34,\"string_mode\":true,\"toss\":[97],\
+         \"toss_size\":2,\"stacks\":1}\n\
+         {\"tick\":6,\"ip\":0,\"position\":[6,0],\"delta\":[1,0],\"offset\":[0,0],\
+         \"instruction\":\"@\",\"value\":64,\"string_mode\":false,\"toss\":[],\
+         \"toss_size\":0,\"stacks\":1}\n"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_profile() -> Result<()> {
+    // puts an `A` at (-1, 0) and counts down from 5
+    let mut program = Program::<i32>::builder("5\"A\"01-0pv\n         >1-:v\n         ^   _@")
+        .profile()
+        .build();
+    program.run()?;
+
+    let profile = program.profile().unwrap();
+    assert_eq!(profile.ticks(), profile.executions());
+    assert_eq!(profile.instructions().get(&('_' as i32)), Some(&5));
+    assert_eq!(
+        profile
+            .cells()
+            .find(|(position, _)| **position == (13, 2, 0)),
+        Some((&(13, 2, 0), 5))
+    );
+    assert_eq!(profile.resizes(), (1, 0));
+
+    let mut json = Vec::new();
+    profile.write_json(&mut json)?;
+    assert!(String::from_utf8(json)?
+        .starts_with("{\"origin\":[0,0],\"counts\":[[1,1,1,1,1,1,1,1,1,1,0,0,0,0,0],"));
+
+    Ok(())
+}
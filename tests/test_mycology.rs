@@ -8,11 +8,11 @@ const SUFFIX98: &str = ".b98";
 const SUFFIX93: &str = ".bf";
 
 fn testcase93(name: &str) -> String {
-    TESTDIR.to_owned() + name + &SUFFIX93.to_owned()
+    TESTDIR.to_owned() + name + SUFFIX93
 }
 
 fn testcase98(name: &str) -> String {
-    TESTDIR.to_owned() + name + &SUFFIX98.to_owned()
+    TESTDIR.to_owned() + name + SUFFIX98
 }
 
 #[test]
@@ -30,12 +30,19 @@ fn test_mycorand() -> Result<()> {
 
 #[test]
 fn test_mycology() -> Result<()> {
-    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
-    cmd.arg(testcase98("mycology"))
-        .assert()
-        .failure()
-        .code(predicate::eq(15))
-        .stdout(predicate::str::contains("BAD:").not());
+    for engine in ["interpreter", "fast"] {
+        for cell_size in ["i32", "i64", "i128", "bignum"] {
+            let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+            cmd.args(["--engine", engine])
+                .arg("--cell-size")
+                .arg(cell_size)
+                .arg(testcase98("mycology"))
+                .assert()
+                .failure()
+                .code(predicate::eq(15))
+                .stdout(predicate::str::contains("BAD:").not());
+        }
+    }
 
     Ok(())
 }
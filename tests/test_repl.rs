@@ -8,8 +8,7 @@ const PROMPT: &str = "> ";
 fn test_repl_core_behavior() -> Result<()> {
     let line1 = format!("{} - Unefunge 98 REPL", env!("CARGO_PKG_NAME"));
     let line2 = format!("version {}", env!("CARGO_PKG_VERSION"));
-    let line3 =
-        format!("(type \"exit\" or \"quit\" and press <Enter> or press <Ctrl> + C to quit)");
+    let line3 = "(type \"exit\" or \"quit\" and press <Enter> or press <Ctrl> + C to quit)";
 
     for stop in ["exit", "quit"] {
         let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
@@ -44,3 +43,18 @@ fn test_empty_line() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_unefunge_restrictions() -> Result<()> {
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    // `^` reflects, so the line still ends at its implicit `@`
+    cmd.write_stdin("^\n7y.\nexit")
+        .assert()
+        .success()
+        .stdout(predicate::str::ends_with(format!(
+            "{}\n{}1 \n{}",
+            PROMPT, PROMPT, PROMPT
+        )));
+
+    Ok(())
+}
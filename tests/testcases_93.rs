@@ -9,7 +9,7 @@ const TESTDIR: &str = "tests/bf93/";
 const SUFFIX: &str = ".bf";
 
 fn testcase(name: &str) -> String {
-    TESTDIR.to_owned() + name + &SUFFIX.to_owned()
+    TESTDIR.to_owned() + name + SUFFIX
 }
 
 #[test]
@@ -48,6 +48,36 @@ fn test_factorial() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_fast_engine() -> Result<()> {
+    for (name, stdin) in [
+        ("sanity", ""),
+        ("hello_world", ""),
+        ("factorial", "10"),
+        ("primecheck", "7919"),
+        ("compare", "53\n-123"),
+        ("div_by_zero", "3"),
+        ("borders", ""),
+    ] {
+        let mut interpreter = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+        let expected = interpreter
+            .args(["--engine", "interpreter"])
+            .arg(testcase(name))
+            .write_stdin(stdin)
+            .output()?;
+        let mut fast = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+        fast.args(["--engine", "fast"])
+            .arg(testcase(name))
+            .write_stdin(stdin)
+            .assert()
+            .code(expected.status.code().unwrap_or_default())
+            .stdout(expected.stdout)
+            .stderr(expected.stderr);
+    }
+
+    Ok(())
+}
+
 #[test]
 fn test_quine() -> Result<()> {
     let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
@@ -106,10 +136,10 @@ fn test_echo() -> Result<()> {
         .collect();
     let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
     cmd.arg(filename)
-        .write_stdin(format!("{}", c))
+        .write_stdin(c.clone())
         .assert()
         .success()
-        .stdout(format!("{}", c));
+        .stdout(c);
     Ok(())
 }
 
@@ -135,8 +165,17 @@ fn test_getout() -> Result<()> {
 #[test]
 fn test_div_by_zero() -> Result<()> {
     let filename = testcase("div_by_zero");
+    // Befunge-93 asks the user for the result
     let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
-    cmd.arg(filename)
+    cmd.arg(&filename)
+        .write_stdin("7")
+        .assert()
+        .success()
+        .stdout("What do you want 5/0 to be? 7 ");
+
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    cmd.arg("--std=98")
+        .arg(filename)
         .assert()
         .success()
         .stdout("0 ")
@@ -191,3 +230,44 @@ fn test_read_int_limits() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_read_at_eof() -> Result<()> {
+    for (standard, expected) in [("93", "-1 "), ("98", "0 ")] {
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+        cmd.arg(format!("--std={}", standard))
+            .arg(testcase("echo_int"))
+            .assert()
+            .success()
+            .stdout(expected);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_standards() -> Result<()> {
+    for (name, expected_93, expected_98) in [
+        // every space is pushed in string mode
+        ("string_spaces", "b  a", "b a"),
+        // Funge-Space is a fixed 80x25 torus, so cells can not be put outside of it
+        ("fixed_grid", " ", "A"),
+        // `;` is not a Befunge-93 instruction and reflects
+        ("undefined", "", "2 "),
+    ] {
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+        cmd.arg(testcase(name))
+            .assert()
+            .success()
+            .stdout(expected_93);
+
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+        cmd.arg("--std=98")
+            .arg(testcase(name))
+            .assert()
+            .success()
+            .stdout(expected_98);
+    }
+
+    Ok(())
+}
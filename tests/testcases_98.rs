@@ -6,7 +6,7 @@ const TESTDIR: &str = "tests/bf98/";
 const SUFFIX: &str = ".b98";
 
 fn testcase(name: &str) -> String {
-    TESTDIR.to_owned() + name + &SUFFIX.to_owned()
+    TESTDIR.to_owned() + name + SUFFIX
 }
 
 #[test]
@@ -60,6 +60,17 @@ fn test_k5() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_k_wrap() -> Result<()> {
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    cmd.arg(testcase("k_wrap"))
+        .assert()
+        .success()
+        .stdout("6 5 4 0 ");
+
+    Ok(())
+}
+
 #[test]
 fn test_non_cardinal() -> Result<()> {
     let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
@@ -127,6 +138,84 @@ fn test_diagonal_negspace() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_far_apart() -> Result<()> {
+    for storage in ["auto", "sparse"] {
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+        cmd.args(["--storage", storage])
+            .arg(testcase("far_apart"))
+            .assert()
+            .success()
+            .stderr("")
+            .stdout("K");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_sparse_storage() -> Result<()> {
+    for name in ["diagonal_negspace", "roundabout", "outside_twice", "split"] {
+        let mut dense = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+        let expected = dense
+            .args(["--storage", "dense"])
+            .arg(testcase(name))
+            .output()?;
+        let mut sparse = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+        sparse
+            .args(["--storage", "sparse"])
+            .arg(testcase(name))
+            .assert()
+            .code(expected.status.code().unwrap_or_default())
+            .stdout(expected.stdout)
+            .stderr(expected.stderr);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_fast_engine() -> Result<()> {
+    for name in [
+        "hello_world",
+        "k1",
+        "k_wrap",
+        "roundabout",
+        "pg_out",
+        "far_apart",
+        "split",
+        "non_cardinal",
+        "least_division",
+    ] {
+        let mut interpreter = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+        let expected = interpreter
+            .args(["--engine", "interpreter"])
+            .arg(testcase(name))
+            .output()?;
+        let mut fast = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+        fast.args(["--engine", "fast"])
+            .arg(testcase(name))
+            .assert()
+            .code(expected.status.code().unwrap_or_default())
+            .stdout(expected.stdout)
+            .stderr(expected.stderr);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_least_division() -> Result<()> {
+    // the least cell divided by -1 wraps around, and its remainder is 0
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    cmd.arg(testcase("least_division"))
+        .assert()
+        .success()
+        .stdout("-2147483648 0 ");
+
+    Ok(())
+}
+
 #[test]
 fn test_outside_twice() -> Result<()> {
     let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
@@ -138,3 +227,437 @@ fn test_outside_twice() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_split() -> Result<()> {
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    // the child IP (with ID 1) executes before its parent (with ID 0)
+    cmd.arg(testcase("split"))
+        .assert()
+        .success()
+        .stderr("")
+        .stdout("1 0 ");
+
+    Ok(())
+}
+
+#[test]
+fn test_split_quit() -> Result<()> {
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    // `q` terminates every IP, including the child that loops forever
+    cmd.arg(testcase("split_quit"))
+        .assert()
+        .failure()
+        .code(3)
+        .stdout("");
+
+    Ok(())
+}
+
+#[test]
+fn test_input_file() -> Result<()> {
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    cmd.arg(testcase("input_file"))
+        .assert()
+        .success()
+        .stderr("")
+        .stdout("5 0 2 2 ad");
+
+    Ok(())
+}
+
+#[test]
+fn test_input_file_missing() -> Result<()> {
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    cmd.arg(testcase("input_file_missing"))
+        .assert()
+        .success()
+        .stderr("")
+        .stdout("OK");
+
+    Ok(())
+}
+
+#[test]
+fn test_output_file() -> Result<()> {
+    let workdir = std::env::temp_dir().join("bff_test_output_file");
+    std::fs::create_dir_all(&workdir)?;
+    let program = std::env::current_dir()?.join(testcase("output_file"));
+
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    cmd.arg(program)
+        .current_dir(&workdir)
+        .assert()
+        .success()
+        .stderr("")
+        .stdout("3 0 1 3 Hey");
+    // trailing spaces are stripped in text mode
+    assert_eq!(std::fs::read_to_string(workdir.join("out"))?, "Hey");
+
+    Ok(())
+}
+
+#[test]
+fn test_output_file_clamped() -> Result<()> {
+    let workdir = std::env::temp_dir().join("bff_test_output_file_clamped");
+    std::fs::create_dir_all(&workdir)?;
+    let program = std::env::current_dir()?.join(testcase("output_file_clamped"));
+
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    cmd.arg(&program)
+        .current_dir(&workdir)
+        .assert()
+        .success()
+        .stderr("")
+        .stdout("OK");
+    // the huge box is clamped to the bounds of Funge-Space i.e., to the program itself
+    assert_eq!(
+        std::fs::read_to_string(workdir.join("out"))?,
+        std::fs::read_to_string(program)?.trim_end()
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_execute_disabled() -> Result<()> {
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    cmd.arg(testcase("execute_disabled"))
+        .assert()
+        .success()
+        .stderr("")
+        .stdout("OK");
+
+    Ok(())
+}
+
+#[test]
+fn test_execute() -> Result<()> {
+    for paradigm in ["system", "exec"] {
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+        cmd.arg("--exec")
+            .arg(paradigm)
+            .arg("-u")
+            .arg("0\"eslaf\"=.@")
+            .assert()
+            .success()
+            .stdout("1 ");
+    }
+
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    cmd.arg("--exec")
+        .arg("system")
+        .arg(testcase("execute"))
+        .assert()
+        .success()
+        .stdout("3 ");
+
+    Ok(())
+}
+
+#[test]
+fn test_operating_paradigm() -> Result<()> {
+    for (paradigm, expected) in [("disabled", "0 "), ("system", "1 "), ("exec", "2 ")] {
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+        cmd.arg("--exec")
+            .arg(paradigm)
+            .arg("-u")
+            .arg("5y.@")
+            .assert()
+            .success()
+            .stdout(expected);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_fingerprints() -> Result<()> {
+    for (code, expected) in [
+        ("\"AMOR\"4($$MDCLXVI++++++.@", "1666 "),
+        ("\"LOOB\"4($$cfA.@", "12 "),
+        ("\"UDOM\"4($$703-M.@", "-2 "),
+        ("\"PSPF\"4($$3F2FDP@", "1.500000 "),
+        ("\"PDPF\"4($$2FQP@", "1.414214 "),
+        ("\"NRTS\"4($$0\"olleh\"N.D@", "5 hello"),
+        ("\"ETAD\"4($$54*aa**11W.@", "5 "),
+        ("\"ITRH\"4($$G.@", "1 "),
+        ("\"CFER\"4($$5R6R$D.@", "5 "),
+    ] {
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+        cmd.arg("-u")
+            .arg(code)
+            .assert()
+            .success()
+            .stderr("")
+            .stdout(expected);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_fingerprint_null() -> Result<()> {
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    cmd.arg(testcase("null"))
+        .assert()
+        .success()
+        .stderr("")
+        .stdout("OK");
+
+    Ok(())
+}
+
+#[test]
+fn test_fingerprint_strn_input() -> Result<()> {
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    cmd.arg("-u")
+        .arg("\"NRTS\"4($$ID@")
+        .write_stdin("hello world\n")
+        .assert()
+        .success()
+        .stdout("hello world");
+
+    Ok(())
+}
+
+#[test]
+fn test_cell_size() -> Result<()> {
+    // 225^4 overflows 32 bit cells; `y` item 2 is the number of bytes per cell
+    for (cell_size, expected) in [
+        ("i32", "-1732076671 4 "),
+        ("i64", "2562890625 8 "),
+        ("i128", "2562890625 16 "),
+        ("bignum", "2562890625 0 "),
+    ] {
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+        cmd.arg("--cell-size")
+            .arg(cell_size)
+            .arg("-u")
+            .arg("ff*:*:*.2y.@")
+            .assert()
+            .success()
+            .stdout(expected);
+    }
+
+    // 225^32 does not fit even in 128 bits
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    cmd.arg("--cell-size")
+        .arg("bignum")
+        .arg("-u")
+        .arg("ff*:*:*:*:*:*.@")
+        .assert()
+        .success()
+        .stderr("")
+        .stdout("1861403728794734215467410604755702820123364205073812627233564853668212890625 ");
+
+    Ok(())
+}
+
+#[test]
+fn test_huge_block_counts() -> Result<()> {
+    // 225^16 fits in 128 bit cells but not in a usize: `{` moves the whole stack,
+    // `}` empties the SOSS, and the zeros that `{` and `}` would have to push reflect
+    for cell_size in ["i128", "bignum"] {
+        for (code, expected) in [
+            ("12ff*:*:*:*{..@", "2 1 "),
+            ("0ff*:*:*:*-{1.@.2", "2 "),
+            ("0{ff*:*:*:*}1.@.2", "2 "),
+            ("0{0ff*:*:*:*-}1.@.2", "1 "),
+            ("ff*:*:*:*:*{@", ""),
+        ] {
+            let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+            cmd.arg("--cell-size")
+                .arg(cell_size)
+                .arg("-u")
+                .arg(code)
+                .assert()
+                .success()
+                .stdout(expected);
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_greatest_coordinate() -> Result<()> {
+    // 2^31 - 1 (built with overflowing arithmetic) is the greatest coordinate of 32 bit cells,
+    // where a cell can be put and got like anywhere else
+    for storage in ["auto", "sparse"] {
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+        cmd.arg("--storage")
+            .arg(storage)
+            .arg("-u")
+            .arg("2:*:*:*:*2:*:*:*:*2/*1-:\"A\"\\pg.@")
+            .assert()
+            .success()
+            .stdout("65 ");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_trefunge() -> Result<()> {
+    // `.tf` files are run as Trefunge programs
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    cmd.arg(TESTDIR.to_owned() + "trefunge.tf")
+        .assert()
+        .success()
+        .stderr("")
+        .stdout("3 ZOK");
+
+    // any other file can be run as one with `--trefunge`
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    cmd.arg("--trefunge")
+        .arg(testcase("trefunge_wrap"))
+        .assert()
+        .success()
+        .stderr("")
+        .stdout("OK");
+
+    Ok(())
+}
+
+#[test]
+fn test_unefunge() -> Result<()> {
+    for (code, expected) in [
+        // `y` reports a single dimension
+        ("7y.@", "1 "),
+        // `v` does not exist in Unefunge and reflects, instead of looping forever
+        ("5v@.", "5 "),
+        // `p` and `g` pop vectors with a single component
+        ("\"A\"fpfg,@", "A"),
+    ] {
+        let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+        cmd.arg("-u")
+            .arg(code)
+            .assert()
+            .success()
+            .stderr("")
+            .stdout(expected);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_trace() -> Result<()> {
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    let output = cmd
+        .args(["--trace", &testcase("debug")])
+        .assert()
+        .success()
+        .stdout("3 ")
+        .get_output()
+        .stderr
+        .clone();
+    let records: Vec<&str> = str::from_utf8(&output)?.lines().collect();
+    assert_eq!(records.len(), 11);
+    assert_eq!(
+        records[8],
+        "{\"tick\":8,\"ip\":0,\"position\":[8,0],\"delta\":[1,0],\"offset\":[0,0],\
+         \"instruction\":\"p\",\"value\":112,\"string_mode\":false,\"toss\":[0,15,65,3],\
+         \"toss_size\":4,\"stacks\":1}"
+    );
+
+    let path = std::env::temp_dir().join(format!("bff-trace-{}.jsonl", std::process::id()));
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    cmd.args([
+        "--trace",
+        "--trace-top",
+        "0",
+        "--trace-region",
+        "-1:2",
+        "--trace-file",
+    ])
+    .arg(&path)
+    .args(["-u", "1-2-3-@"])
+    .assert()
+    .success()
+    .stderr("");
+    let trace = std::fs::read_to_string(&path)?;
+    std::fs::remove_file(&path)?;
+    assert_eq!(
+        trace,
+        "{\"tick\":0,\"ip\":0,\"position\":[0],\"delta\":[1],\"offset\":[0],\
+         \"instruction\":\"1\",\"value\":49,\"string_mode\":false,\"toss\":[],\
+         \"toss_size\":0,\"stacks\":1}\n\
+         {\"tick\":1,\"ip\":0,\"position\":[1],\"delta\":[1],\"offset\":[0],\
+         \"instruction\":\"-\",\"value\":45,\"string_mode\":false,\"toss\":[],\
+         \"toss_size\":1,\"stacks\":1}\n\
+         {\"tick\":2,\"ip\":0,\"position\":[2],\"delta\":[1],\"offset\":[0],\
+         \"instruction\":\"2\",\"value\":50,\"string_mode\":false,\"toss\":[],\
+         \"toss_size\":1,\"stacks\":1}\n"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_profile() -> Result<()> {
+    let path = std::env::temp_dir().join(format!("bff-profile-{}.csv", std::process::id()));
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    let output = cmd
+        .args(["--profile", "--profile-csv"])
+        .arg(&path)
+        .arg(testcase("profile"))
+        .assert()
+        .success()
+        .stdout("")
+        .get_output()
+        .stderr
+        .clone();
+    let summary = str::from_utf8(&output)?;
+    assert!(summary.contains("  ticks                   41\n"));
+    assert!(summary.contains("118 'v'                  6   14.63%\n"));
+    assert!(summary.contains("(5, 2)                  95 '_'                       5\n"));
+    assert!(summary.ends_with(
+        "Heat map from (0, 0) to (6, 2) ('░' to '█' is 1 to 5 executions)\n\
+         ░░     \n \
+         █████ \n \
+         █  ██░\n"
+    ));
+
+    let csv = std::fs::read_to_string(&path)?;
+    std::fs::remove_file(&path)?;
+    assert_eq!(
+        csv,
+        ",0,1,2,3,4,5,6\n\
+         0,1,1,0,0,0,0,0\n\
+         1,0,5,5,5,5,5,0\n\
+         2,0,4,0,0,4,5,1\n"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_profile_far_apart() -> Result<()> {
+    // the IP runs into a `v` at (1000, 0) and an `@` at (1000, 1000),
+    // so the empty rows and columns between them are left out
+    let mut cmd = Command::cargo_bin(env!("CARGO_PKG_NAME"))?;
+    let output = cmd
+        .args(["--profile", "--profile-json", "/dev/stdout"])
+        .arg(testcase("profile_far_apart"))
+        .assert()
+        .success()
+        .get_output()
+        .clone();
+    assert!(str::from_utf8(&output.stderr)?.ends_with(
+        "Heat map from (0, 0) to (1000, 1000) ('░' to '█' is 1 to 1 executions)\n\
+         █ ████████ ████████┊█\n                   \
+         ┊█\n\
+         ┈┈┈┈┈┈┈┈┈┈┈┈┈┈┈┈┈┈┈┈┈\n                   \
+         ┊█\n"
+    ));
+    assert_eq!(
+        str::from_utf8(&output.stdout)?,
+        "{\"origin\":[0,0],\"counts\":[[1,0,1,1,1,1,1,1,1,1,0,1,1,1,1,1,1,1,1,1],\
+         [0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,1],[0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,1]],\
+         \"coordinates\":[[0,1,2,3,4,5,6,7,8,9,10,11,12,13,14,15,16,17,18,1000],[0,1,1000]]}\n"
+    );
+
+    Ok(())
+}